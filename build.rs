@@ -3,6 +3,7 @@ fn main() {
   tonic_build::configure()
     .out_dir("src/pb")
     .protoc_arg("--experimental_allow_proto3_optional")
+    .type_attribute(".", "#[derive(serde::Serialize)]")
     .compile(
       &[
         "proto/checkpoint.proto",
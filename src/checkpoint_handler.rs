@@ -1,15 +1,24 @@
 use eyre::{Result, Report};
-use backoff::{ExponentialBackoff, future::retry};
+use backoff::{ExponentialBackoff, future::retry_notify};
 use jsonrpsee::http_client::{HttpClient};
 use futures::future::join_all;
 use futures::FutureExt;
 use sui_indexer::{models::objects::ObjectStatus, types::CheckpointTransactionBlockResponse, store::CheckpointData};
-use sui_json_rpc::api::ReadApiClient;
-use sui_types::base_types::{TransactionDigest, ObjectID, SequenceNumber};
+use sui_json_rpc::api::{ReadApiClient, GovernanceReadApiClient};
+use sui_types::{
+  SUI_SYSTEM_STATE_OBJECT_ID, TypeTag, base_types::{TransactionDigest, ObjectID, SequenceNumber, SuiAddress},
+  dynamic_field::Field, object::Owner,
+  sui_system_state::{
+    SuiSystemStateTrait, sui_system_state_inner_v1::SuiSystemStateInnerV1,
+    sui_system_state_summary::SuiSystemStateSummary,
+  },
+};
 use sui_json_rpc_types::{
   Checkpoint, OwnedObjectRef, SuiTransactionBlockEffects, SuiObjectData, SuiTransactionBlockEffectsAPI,
-  SuiGetPastObjectRequest, SuiObjectDataOptions, SuiTransactionBlockResponseOptions
+  SuiGetPastObjectRequest, SuiObjectDataOptions, SuiTransactionBlockResponseOptions, SuiRawData, SuiRawMoveObject,
 };
+use tracing::{Instrument, debug_span, instrument};
+use crate::metrics::{METRICS, observe_rpc};
 
 const MULTI_GET_CHUNK_SIZE: usize = 50;
 
@@ -17,14 +26,23 @@ type CheckpointSequenceNumber = u64;
 
 pub struct CheckpointHandler {
   http_client: HttpClient,
+  transaction_options: SuiTransactionBlockResponseOptions,
+  object_options: SuiObjectDataOptions,
+  backoff: ExponentialBackoff,
 }
 
 impl CheckpointHandler {
   pub fn new(
     http_client: HttpClient,
+    transaction_options: SuiTransactionBlockResponseOptions,
+    object_options: SuiObjectDataOptions,
+    backoff: ExponentialBackoff,
   ) -> Self {
     Self {
       http_client,
+      transaction_options,
+      object_options,
+      backoff,
     }
   }
 
@@ -32,7 +50,11 @@ impl CheckpointHandler {
   pub async fn download_checkpoint_data(&self, seq: CheckpointSequenceNumber) -> Result<CheckpointData> {
     let checkpoint = self.get_checkpoint(seq).await?;
     let transactions = join_all(checkpoint.transactions.chunks(MULTI_GET_CHUNK_SIZE)
-    .map(|digests| Self::multi_get_full_transactions(self.http_client.clone(), digests.to_vec())))
+    .enumerate()
+    .map(|(chunk, digests)| {
+      Self::multi_get_full_transactions(self.http_client.clone(), digests.to_vec(), self.transaction_options.clone())
+      .instrument(debug_span!("multi_get_transaction_blocks", seq, chunk, size = digests.len()))
+    }))
     .await
     .into_iter()
     .try_fold(vec![], |mut acc, chunk| {
@@ -44,7 +66,11 @@ impl CheckpointHandler {
     .iter()
     .flat_map(|tx| Self::get_object_changes(&tx.effects))
     .collect::<Vec<_>>();
-    let changed_objects = Self::fetch_changed_objects(self.http_client.clone(), object_changes).await?;
+    let changed_objects = Self::fetch_changed_objects(
+      self.http_client.clone(),
+      object_changes,
+      self.object_options.clone(),
+    ).await?;
 
     Ok(CheckpointData {
       checkpoint,
@@ -53,21 +79,122 @@ impl CheckpointHandler {
     })
   }
 
-  async fn get_checkpoint(&self, seq: CheckpointSequenceNumber) -> Result<Checkpoint> {
-    let checkpoint = retry(ExponentialBackoff::default(), || async {
-      let checkpoint = self.http_client
-      .get_checkpoint(seq.into())
+  #[instrument(level = "debug", skip(self))]
+  pub async fn get_checkpoint(&self, seq: CheckpointSequenceNumber) -> Result<Checkpoint> {
+    let checkpoint = retry_notify(self.backoff.clone(), || async {
+      let checkpoint = observe_rpc("sui_getCheckpoint", self.http_client.get_checkpoint(seq.into()))
       .await
       .map_err(|e| {
         Report::msg(format!("Failed to get checkpoint with sequence number {} and error {:?}", seq, e))
       })?;
 
       Ok(checkpoint)
-    }).await?;
+    }, METRICS.retry_notifier("get_checkpoint")).await?;
 
     Ok(checkpoint)
   }
 
+  pub async fn get_latest_checkpoint_sequence_number(&self) -> Result<CheckpointSequenceNumber> {
+    let seq = retry_notify(self.backoff.clone(), || async {
+      let seq = observe_rpc(
+        "sui_getLatestCheckpointSequenceNumber",
+        self.http_client.get_latest_checkpoint_sequence_number(),
+      )
+      .await
+      .map_err(|e| {
+        Report::msg(format!("Failed to get latest checkpoint sequence number with error {:?}", e))
+      })?;
+
+      Ok(seq)
+    }, METRICS.retry_notifier("get_latest_checkpoint_sequence_number")).await?.into_inner();
+    METRICS.set_network_tip_checkpoint(seq);
+
+    Ok(seq)
+  }
+
+  /// Fullnodes only serve the system state of the epoch they are currently in.
+  pub async fn get_latest_sui_system_state(&self) -> Result<SuiSystemStateSummary> {
+    let system_state = retry_notify(self.backoff.clone(), || async {
+      let system_state = observe_rpc("suix_getLatestSuiSystemState", self.http_client.get_latest_sui_system_state())
+      .await
+      .map_err(|e| {
+        Report::msg(format!("Failed to get latest sui system state with error {:?}", e))
+      })?;
+
+      Ok(system_state)
+    }, METRICS.retry_notifier("get_latest_sui_system_state")).await?;
+
+    Ok(system_state)
+  }
+
+  /// The system state as left by a `ChangeEpoch` transaction i.e. at the start of the epoch it begins. The inner
+  /// state is kept in a dynamic field of the 0x5 object, which the transaction mutates, so it can be read at that
+  /// version even once the node has moved on to later epochs.
+  pub async fn get_sui_system_state_at(&self, effects: &SuiTransactionBlockEffects) -> Result<SuiSystemStateSummary> {
+    let system_state_owner = Owner::ObjectOwner(SuiAddress::from(SUI_SYSTEM_STATE_OBJECT_ID));
+    let requests = effects
+    .mutated()
+    .iter()
+    .filter(|o| o.owner == system_state_owner)
+    .map(|o| SuiGetPastObjectRequest {
+      object_id: o.reference.object_id,
+      version: o.reference.version,
+    })
+    .collect::<Vec<_>>();
+
+    let responses = retry_notify(self.backoff.clone(), || async {
+      let responses = observe_rpc("sui_tryMultiGetPastObjects", self.http_client.try_multi_get_past_objects(
+        requests.clone(),
+        Some(SuiObjectDataOptions::bcs_lossless()),
+      ))
+      .await
+      .map_err(|e| {
+        Report::msg(format!("Failed to get the system state objects {:?} with error {:?}", requests, e))
+      })?;
+
+      Ok(responses)
+    }, METRICS.retry_notifier("get_sui_system_state_at")).await?;
+
+    for response in responses {
+      let object_data = response.into_object()?;
+
+      if let Some(SuiRawData::MoveObject(raw)) = &object_data.bcs {
+        if let Some(system_state) = Self::decode_sui_system_state(raw)? {
+          return Ok(system_state)
+        }
+      }
+    }
+
+    Err(Report::msg(format!(
+      "The system state is not among the objects {:?} mutated by transaction {}",
+      requests,
+      effects.transaction_digest(),
+    )))
+  }
+
+  /// Decodes `0x2::dynamic_field::Field<u64, 0x3::sui_system_state_inner::SuiSystemStateInnerV*>`. Returns `None`
+  /// for any other object.
+  fn decode_sui_system_state(raw: &SuiRawMoveObject) -> Result<Option<SuiSystemStateSummary>> {
+    let inner_type = match raw.type_.type_params.get(1) {
+      Some(TypeTag::Struct(inner_type)) if raw.type_.name.as_str() == "Field" => inner_type,
+      _ => return Ok(None),
+    };
+
+    match inner_type.name.as_str() {
+      "SuiSystemStateInnerV1" => {
+        let field = bcs::from_bytes::<Field<u64, SuiSystemStateInnerV1>>(&raw.bcs_bytes).map_err(|e| {
+          Report::msg(format!("Failed to decode the system state with error {:?}", e))
+        })?;
+
+        Ok(Some(field.value.into_sui_system_state_summary()))
+      }
+      name if name.starts_with("SuiSystemStateInner") => {
+        Err(Report::msg(format!("Unsupported system state version {}", name)))
+      }
+      _ => Ok(None),
+    }
+  }
+
     // TODO(gegaowp): re-orgnize object util functions below
   pub fn get_object_changes(effects: &SuiTransactionBlockEffects,) -> Vec<(ObjectID, SequenceNumber, ObjectStatus)> {
     let created = effects.created().iter().map(|o: &OwnedObjectRef| {
@@ -86,8 +213,9 @@ impl CheckpointHandler {
   pub async fn fetch_changed_objects(
     http_client: HttpClient,
     object_changes: Vec<(ObjectID, SequenceNumber, ObjectStatus)>,
+    options: SuiObjectDataOptions,
   ) -> Result<Vec<(ObjectStatus, SuiObjectData)>> {
-    join_all(object_changes.chunks(MULTI_GET_CHUNK_SIZE).map(|objects| {
+    join_all(object_changes.chunks(MULTI_GET_CHUNK_SIZE).enumerate().map(|(chunk, objects)| {
         let wanted_past_object_statuses: Vec<ObjectStatus> =objects.iter().map(|(_, _, status)| *status).collect();
         let wanted_past_object_request = objects
         .iter()
@@ -97,12 +225,12 @@ impl CheckpointHandler {
         })
         .collect();
 
-        http_client
-        .try_multi_get_past_objects(
+        observe_rpc("sui_tryMultiGetPastObjects", http_client.try_multi_get_past_objects(
           wanted_past_object_request,
-          Some(SuiObjectDataOptions::bcs_lossless()),
-        )
+          Some(options.clone()),
+        ))
         .map(move |resp| (resp, wanted_past_object_statuses))
+        .instrument(debug_span!("try_multi_get_past_objects", chunk, size = objects.len()))
     }))
     .await
     .into_iter()
@@ -124,18 +252,12 @@ impl CheckpointHandler {
   pub async fn multi_get_full_transactions(
     http_client: HttpClient,
     digests: Vec<TransactionDigest>,
+    options: SuiTransactionBlockResponseOptions,
   ) -> Result<Vec<CheckpointTransactionBlockResponse>> {
-    let sui_transactions = http_client.multi_get_transaction_blocks(
+    let sui_transactions = observe_rpc("sui_multiGetTransactionBlocks", http_client.multi_get_transaction_blocks(
       digests.clone(),
-      // MUSTFIX(gegaowp): avoid double fetching both input and raw_input
-      Some(
-        SuiTransactionBlockResponseOptions::new()
-        .with_input()
-        .with_effects()
-        .with_events()
-        .with_raw_input(),
-      ),
-    )
+      Some(options),
+    ))
     .await
     .map_err(|e| {
       Report::msg(format!("Failed to get transactions {:?} with error: {:?}", digests.clone(), e))
@@ -0,0 +1,54 @@
+use std::collections::{HashMap, HashSet};
+use sui_indexer::store::CheckpointData;
+use sui_json_rpc_types::{
+  SuiExecutionStatus, SuiTransactionBlockData, SuiTransactionBlockEffectsAPI, SuiTransactionBlockKind, SuiCommand,
+};
+use crate::pb::sui::checkpoint as pb;
+
+/// Computes the aggregated statistics of an already downloaded checkpoint.
+pub fn compute_checkpoint_stats(source: &CheckpointData) -> pb::CheckpointStats {
+  let mut stats = pb::CheckpointStats {
+    sequence_number: source.checkpoint.sequence_number,
+    transactions: source.transactions.len() as u64,
+    gas_used: Some(pb::GasCostSummary::default()),
+    ..Default::default()
+  };
+  let mut senders = HashSet::new();
+  let mut move_calls_per_package = HashMap::new();
+
+  for tx in &source.transactions {
+    match tx.effects.status() {
+      SuiExecutionStatus::Success => stats.successful_transactions += 1,
+      SuiExecutionStatus::Failure {..} => stats.failed_transactions += 1,
+    }
+
+    stats.events += tx.events.data.len() as u64;
+    stats.created_objects += tx.effects.created().len() as u64;
+    stats.mutated_objects += tx.effects.mutated().len() as u64;
+    stats.deleted_objects += tx.effects.deleted().len() as u64;
+
+    let gas_cost_summary = tx.effects.gas_cost_summary();
+    if let Some(gas_used) = stats.gas_used.as_mut() {
+      gas_used.computation_cost += gas_cost_summary.computation_cost;
+      gas_used.storage_cost += gas_cost_summary.storage_cost;
+      gas_used.storage_rebate += gas_cost_summary.storage_rebate;
+      gas_used.non_refundable_storage_fee += gas_cost_summary.non_refundable_storage_fee;
+    }
+
+    let SuiTransactionBlockData::V1(data) = &tx.transaction.data;
+    senders.insert(data.sender);
+
+    if let SuiTransactionBlockKind::ProgrammableTransaction(ptb) = &data.transaction {
+      for command in &ptb.commands {
+        if let SuiCommand::MoveCall(move_call) = command {
+          *move_calls_per_package.entry(move_call.package.to_canonical_string()).or_insert(0) += 1;
+        }
+      }
+    }
+  }
+
+  stats.distinct_senders = senders.len() as u64;
+  stats.move_calls_per_package = move_calls_per_package;
+
+  stats
+}
@@ -0,0 +1,317 @@
+use std::{collections::BTreeMap, fs, net::SocketAddr, time::Duration};
+use backoff::ExponentialBackoff;
+use clap::ValueEnum;
+use eyre::{Result, Report};
+use serde::{Serialize, Deserialize};
+use crate::{
+  projection::{Projection, OutputProfile, Field}, position::CheckpointPosition,
+  filter::{
+    Filter, address::AddressWatcher, expr::FilterExpr, rules::{FilterRules, deserialize_addresses},
+  },
+};
+
+/// The effective configuration of the streamer. It is loaded from an optional TOML file, and then environment
+/// variables and CLI flags are applied on top of it, in that order of precedence.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+  /// Chain Identifier is the digest of the genesis checkpoint
+  pub chain_id: String,
+
+  /// Which checkpoint should we start streaming data from. Either a sequence number, `epoch:<n>` for the first
+  /// checkpoint of an epoch, an RFC 3339 timestamp for the first checkpoint at or after it, or `latest`
+  pub starting_checkpoint_seq: CheckpointPosition,
+
+  /// The last checkpoint to stream, inclusive. Either a sequence number, `epoch:<n>` for the last checkpoint of an
+  /// epoch, an RFC 3339 timestamp for the last checkpoint at or before it, or `latest`. Streams forever if not set
+  pub stop_checkpoint_seq: Option<CheckpointPosition>,
+
+  /// A file where the next checkpoint to stream is saved on exit. If it exists at startup, streaming resumes
+  /// from it instead of the starting checkpoint
+  pub cursor_path: Option<String>,
+
+  pub rpc: RpcConfig,
+  pub node: NodeConfig,
+  pub http: HttpConfig,
+  pub watchdog: WatchdogConfig,
+  pub log: LogConfig,
+  pub output: OutputConfig,
+  pub filter: FilterConfig,
+  pub retry: RetryConfig,
+}
+
+impl Default for Config {
+  fn default() -> Self {
+    Self {
+      chain_id: "4btiuiMPvEENsttpZC7CZ53DruC3MAgfznDbASZ7DR6S".to_string(),
+      starting_checkpoint_seq: CheckpointPosition::Seq(0),
+      stop_checkpoint_seq: None,
+      cursor_path: None,
+      rpc: Default::default(),
+      node: Default::default(),
+      http: Default::default(),
+      watchdog: Default::default(),
+      log: Default::default(),
+      output: Default::default(),
+      filter: Default::default(),
+      retry: Default::default(),
+    }
+  }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RpcConfig {
+  /// The fullnode JSON-RPC url. If not set, a local sui-node is spun up instead
+  pub url: Option<String>,
+
+  /// Switched to in turn by the watchdog when streaming stalls
+  pub fallback_urls: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct NodeConfig {
+  /// The sui-node binary, looked up in `PATH` unless it is a path
+  pub binary: String,
+
+  /// The config file of the local sui-node. Its JSON-RPC address is read from the `json-rpc-address` setting
+  pub config_path: Option<String>,
+
+  /// Passed to the sui-node after `--config-path`
+  pub args: Vec<String>,
+
+  /// The sui-node output is written to this file instead of stderr, as stdout only carries the Firehose protocol
+  pub log_path: Option<String>,
+
+  /// Prepended to every line of the sui-node output
+  pub log_prefix: String,
+
+  /// How long to wait for the sui-node to answer after it starts, and for it to make progress while it syncs
+  /// up to the starting checkpoint
+  pub ready_timeout_secs: u64,
+
+  /// Environment variables set on top of the ones of this process
+  pub env: BTreeMap<String, String>,
+  pub restart: RestartConfig,
+}
+
+impl Default for NodeConfig {
+  fn default() -> Self {
+    Self {
+      binary: "sui-node".to_string(),
+      config_path: None,
+      args: vec![],
+      log_path: None,
+      log_prefix: "[sui-node]".to_string(),
+      ready_timeout_secs: 300,
+      env: BTreeMap::new(),
+      restart: Default::default(),
+    }
+  }
+}
+
+/// When the local sui-node should be restarted after it exits
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum RestartPolicy {
+  /// Exit as soon as the sui-node exits
+  Never,
+  /// Restart the sui-node only if it exits with an error
+  #[default]
+  OnFailure,
+  /// Restart the sui-node whenever it exits
+  Always,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RestartConfig {
+  pub policy: RestartPolicy,
+
+  /// Give up after this many consecutive restarts. Restarts forever if 0
+  pub max_attempts: u32,
+  pub initial_delay_ms: u64,
+  pub max_delay_ms: u64,
+
+  /// The restart attempts and delay are reset once the sui-node has been running for this long
+  pub reset_after_secs: u64,
+}
+
+impl Default for RestartConfig {
+  fn default() -> Self {
+    Self {
+      policy: RestartPolicy::default(),
+      max_attempts: 10,
+      initial_delay_ms: 1_000,
+      max_delay_ms: 60_000,
+      reset_after_secs: 300,
+    }
+  }
+}
+
+impl RestartConfig {
+  pub fn backoff(&self) -> ExponentialBackoff {
+    ExponentialBackoff {
+      current_interval: Duration::from_millis(self.initial_delay_ms),
+      initial_interval: Duration::from_millis(self.initial_delay_ms),
+      max_interval: Duration::from_millis(self.max_delay_ms),
+      max_elapsed_time: None,
+      ..Default::default()
+    }
+  }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct HttpConfig {
+  /// Serves the Prometheus metrics on `/metrics` and the health checks on `/healthz` and `/readyz`.
+  /// Disabled if not set
+  pub listen_address: Option<SocketAddr>,
+
+  /// `/healthz` fails once no checkpoint has been emitted for this long while the network tip is moving
+  pub stall_timeout_secs: u64,
+}
+
+impl Default for HttpConfig {
+  fn default() -> Self {
+    Self {
+      listen_address: None,
+      stall_timeout_secs: 300,
+    }
+  }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct WatchdogConfig {
+  /// Recover once no checkpoint has been emitted for this long. Disabled if 0
+  pub stall_timeout_secs: u64,
+}
+
+impl Default for WatchdogConfig {
+  fn default() -> Self {
+    Self {
+      stall_timeout_secs: 300,
+    }
+  }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum LogFormat {
+  #[default]
+  Text,
+  Json,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LogConfig {
+  pub format: LogFormat,
+
+  /// The OTLP gRPC endpoint of an OpenTelemetry collector to export the spans to i.e. http://localhost:4317.
+  /// Disabled if not set
+  pub otlp_endpoint: Option<String>,
+  pub service_name: String,
+}
+
+impl Default for LogConfig {
+  fn default() -> Self {
+    Self {
+      format: LogFormat::default(),
+      otlp_endpoint: None,
+      service_name: "sui-sf-indexer".to_string(),
+    }
+  }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct OutputConfig {
+  pub profile: OutputProfile,
+  pub with_fields: Vec<Field>,
+  pub without_fields: Vec<Field>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct FilterConfig {
+  #[serde(deserialize_with = "deserialize_addresses")]
+  pub watch_addresses: Vec<String>,
+  pub expression: Option<FilterExpr>,
+  pub include: FilterRules,
+  pub exclude: FilterRules,
+}
+
+/// Exponential backoff used to retry failing RPC calls
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RetryConfig {
+  pub initial_interval_ms: u64,
+  pub max_interval_ms: u64,
+  pub multiplier: f64,
+
+  /// Give up after retrying for this long. Retries forever if 0
+  pub max_elapsed_time_secs: u64,
+}
+
+impl Default for RetryConfig {
+  fn default() -> Self {
+    let backoff = ExponentialBackoff::default();
+
+    Self {
+      initial_interval_ms: backoff.initial_interval.as_millis() as u64,
+      max_interval_ms: backoff.max_interval.as_millis() as u64,
+      multiplier: backoff.multiplier,
+      max_elapsed_time_secs: backoff.max_elapsed_time.map(|t| t.as_secs()).unwrap_or_default(),
+    }
+  }
+}
+
+impl RetryConfig {
+  pub fn backoff(&self) -> ExponentialBackoff {
+    ExponentialBackoff {
+      current_interval: Duration::from_millis(self.initial_interval_ms),
+      initial_interval: Duration::from_millis(self.initial_interval_ms),
+      max_interval: Duration::from_millis(self.max_interval_ms),
+      multiplier: self.multiplier,
+      max_elapsed_time: (self.max_elapsed_time_secs > 0).then(|| Duration::from_secs(self.max_elapsed_time_secs)),
+      ..Default::default()
+    }
+  }
+}
+
+impl Config {
+  pub fn load(path: Option<&str>) -> Result<Self> {
+    let path = match path {
+      Some(path) => path,
+      None => return Ok(Self::default()),
+    };
+
+    let content = fs::read_to_string(path).map_err(|e| {
+      Report::msg(format!("Failed to read config file {}: {}", path, e))
+    })?;
+
+    toml::from_str(&content).map_err(|e| {
+      Report::msg(format!("Failed to parse config file {}: {}", path, e))
+    })
+  }
+
+  pub fn to_toml(&self) -> Result<String> {
+    toml::to_string_pretty(self).map_err(|e| Report::msg(format!("Failed to serialize config: {}", e)))
+  }
+
+  pub fn filter(&self) -> Filter {
+    Filter::new(
+      self.filter.include.clone(),
+      self.filter.exclude.clone(),
+      AddressWatcher::new(self.filter.watch_addresses.clone()),
+      self.filter.expression.clone(),
+    )
+  }
+
+  pub fn projection(&self) -> Projection {
+    Projection::new(self.output.profile, &self.output.with_fields, &self.output.without_fields)
+  }
+}
@@ -28,3 +28,25 @@ pub fn convert_checkpoint(source: &Checkpoint) -> pb::Checkpoint {
     validator_signature: source.validator_signature.as_ref().to_vec(),
   }
 }
+
+/// Builds the epoch change record if the given checkpoint is the last one of its epoch. The start of the epoch
+/// i.e. (checkpoint, timestamp) is only known if the streamer has observed the previous epoch boundary.
+pub fn convert_epoch_change(
+  source: &Checkpoint,
+  change_epoch: Option<&pb::SuiChangeEpoch>,
+  epoch_start: Option<(u64, u64)>,
+) -> Option<pb::EpochChange> {
+  let end_of_epoch_data = convert_end_of_epoch_data(source.end_of_epoch_data.as_ref()?);
+
+  Some(pb::EpochChange {
+    epoch: source.epoch,
+    start_checkpoint: epoch_start.map(|(checkpoint, _)| checkpoint),
+    end_checkpoint: source.sequence_number,
+    start_timestamp_ms: epoch_start.map(|(_, timestamp_ms)| timestamp_ms),
+    end_timestamp_ms: source.timestamp_ms,
+    next_epoch_protocol_version: end_of_epoch_data.next_epoch_protocol_version,
+    next_epoch_committee: end_of_epoch_data.next_epoch_committee,
+    epoch_gas_cost_summary: Some(convert_gas_cost_summary(&source.epoch_rolling_gas_cost_summary)),
+    change_epoch: change_epoch.cloned(),
+  })
+}
@@ -1,5 +1,6 @@
 use std::collections::HashMap;
 use base58::ToBase58;
+use log::warn;
 use serde_json::Value;
 use sui_json_rpc_types::{
   SuiArgument, SuiObjectRef, SuiExecutionStatus, SuiTransactionBlockEffectsModifiedAtVersions, OwnedObjectRef,
@@ -8,10 +9,11 @@ use sui_json_rpc_types::{
 };
 use sui_types::{
   base_types::{ObjectID, ObjectType, MoveObjectType, AuthorityName},
-  TypeTag, gas::GasCostSummary, object::Owner, event::EventID, error::SuiObjectResponseError, id::UID,
+  TypeTag, parse_sui_type_tag, gas::GasCostSummary, object::Owner, event::EventID, error::SuiObjectResponseError, id::UID,
   move_package::{TypeOrigin, UpgradeInfo}, messages_checkpoint::CheckpointCommitment, committee::StakeUnit,
 };
 use crate::pb::sui::checkpoint::{self as pb};
+use super::execution_failure::convert_execution_failure;
 
 pub fn convert_sui_object(source: &ObjectID) -> pb::ObjectId {
   pb::ObjectId {
@@ -46,6 +48,22 @@ pub fn convert_type_tag(source: &TypeTag) -> pb::TypeTag {
   }
 }
 
+/// Parses a Move type string such as `0x2::coin::Coin<0x2::sui::SUI>`. Invalid types are not fatal; the parse
+/// error is returned in the message instead.
+pub fn convert_type_tag_str(source: &str) -> pb::ParsedTypeTag {
+  let parsed_type_tag = match parse_sui_type_tag(source) {
+    Ok(type_tag) => pb::parsed_type_tag::ParsedTypeTag::TypeTag(convert_type_tag(&type_tag)),
+    Err(error) => {
+      warn!("Failed to parse Move type '{}': {}", source, error);
+      pb::parsed_type_tag::ParsedTypeTag::Error(error.to_string())
+    },
+  };
+
+  pb::ParsedTypeTag {
+    parsed_type_tag: Some(parsed_type_tag),
+  }
+}
+
 pub fn convert_sui_json_value(source: &Value) -> pb::SuiJsonValue {
   let json_value = match source {
     Value::Null => pb::sui_json_value::Value::Null(()),
@@ -92,6 +110,7 @@ pub fn convert_sui_execution_status(source: &SuiExecutionStatus) -> pb::SuiExecu
     SuiExecutionStatus::Success => pb::sui_execution_status::SuiExecutionStatus::Success(()),
     SuiExecutionStatus::Failure {error} => pb::sui_execution_status::SuiExecutionStatus::Failure(pb::Failure {
       error: error.clone(),
+      parsed_error: convert_execution_failure(error),
     })
   };
   
@@ -318,8 +337,9 @@ pub fn convert_sui_move_value(source: &SuiMoveValue) -> pb::SuiMoveValue {
       id: Some(convert_sui_object(id)),
     }),
     SuiMoveValue::Struct(source) => pb::sui_move_value::SuiMoveValue::Struct(convert_sui_move_struct(source)),
+    // `None` is an empty value, as the option of the proto can't be missing
     SuiMoveValue::Option(source) => pb::sui_move_value::SuiMoveValue::Option(
-      Box::new(source.clone().map(|o| convert_sui_move_value(&o)).unwrap()),
+      Box::new(source.as_ref().as_ref().map(convert_sui_move_value).unwrap_or_default()),
     ),
   };
 
@@ -0,0 +1,66 @@
+use crate::pb::sui::checkpoint as pb;
+
+// The execution error in `SuiExecutionStatus::Failure` is the debug representation of `ExecutionFailureStatus`,
+// optionally followed by the index of the failing command i.e.
+// `MoveAbort(MoveLocation { module: ModuleId { address: 0000..0002, name: Identifier("coin") }, function: 5,
+// instruction: 12, function_name: Some("split") }, 1) in command 2`
+const COMMAND_SUFFIX: &str = " in command ";
+
+fn value_after<'a>(source: &'a str, key: &str) -> Option<&'a str> {
+  source.find(key).map(|i| &source[i + key.len()..])
+}
+
+fn parse_number<T: std::str::FromStr>(source: &str) -> Option<T> {
+  let end = source.find(|c: char| !c.is_ascii_digit()).unwrap_or(source.len());
+  source[..end].parse().ok()
+}
+
+fn parse_quoted(source: &str) -> Option<String> {
+  source.split('"').nth(1).map(str::to_string)
+}
+
+fn parse_move_location(source: &str) -> Option<pb::MoveLocation> {
+  let address = value_after(source, "address: ")?;
+  let address = &address[..address.find(',')?];
+
+  Some(pb::MoveLocation {
+    address: address.to_string(),
+    module: parse_quoted(value_after(source, "name: Identifier(")?)?,
+    function: parse_number(value_after(source, "function: ")?)?,
+    instruction: parse_number(value_after(source, "instruction: ")?)?,
+    function_name: value_after(source, "function_name: Some(").and_then(parse_quoted),
+  })
+}
+
+fn parse_abort_code(source: &str) -> Option<u64> {
+  // The abort code is the last argument of `MoveAbort(MoveLocation { .. }, code)`
+  let code = &source[source.rfind("}, ")? + 3..];
+  parse_number(code)
+}
+
+pub fn convert_execution_failure(error: &str) -> Option<pb::ExecutionFailure> {
+  let (status, command) = match error.rsplit_once(COMMAND_SUFFIX) {
+    Some((status, command)) => (status, Some(command.trim().parse::<u64>().ok()?)),
+    None => (error, None),
+  };
+
+  let kind_end = status.find(|c: char| !c.is_ascii_alphanumeric() && c != '_').unwrap_or(status.len());
+  let kind = &status[..kind_end];
+  if kind.is_empty() {
+    return None
+  }
+
+  let (location, abort_code) = match kind {
+    "MoveAbort" => (Some(parse_move_location(status)?), Some(parse_abort_code(status)?)),
+    // The location is optional here i.e. `MovePrimitiveRuntimeError(MoveLocationOpt(None))`
+    "MovePrimitiveRuntimeError" => (parse_move_location(status), None),
+    _ => (None, None),
+  };
+
+  Some(pb::ExecutionFailure {
+    kind: kind.to_string(),
+    command,
+    location,
+    abort_code,
+  })
+}
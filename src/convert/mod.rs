@@ -7,3 +7,5 @@ pub mod common;
 pub mod sui_gas_data;
 pub mod sui_effects;
 pub mod sui_object_data;
+pub mod execution_failure;
+pub mod sui_system_state;
@@ -0,0 +1,40 @@
+use sui_types::sui_system_state::sui_system_state_summary::{SuiSystemStateSummary, SuiValidatorSummary};
+use crate::pb::sui::checkpoint as pb;
+use super::common::convert_sui_object;
+
+pub fn convert_sui_validator_summary(source: &SuiValidatorSummary) -> pb::SuiValidatorSummary {
+  pb::SuiValidatorSummary {
+    sui_address: hex::encode(source.sui_address),
+    protocol_pubkey: base64::encode(&source.protocol_pubkey_bytes),
+    name: source.name.clone(),
+    voting_power: source.voting_power,
+    gas_price: source.gas_price,
+    commission_rate: source.commission_rate,
+    next_epoch_stake: source.next_epoch_stake,
+    next_epoch_gas_price: source.next_epoch_gas_price,
+    next_epoch_commission_rate: source.next_epoch_commission_rate,
+    staking_pool_id: Some(convert_sui_object(&source.staking_pool_id)),
+    staking_pool_sui_balance: source.staking_pool_sui_balance,
+    rewards_pool: source.rewards_pool,
+    pool_token_balance: source.pool_token_balance,
+    pending_stake: source.pending_stake,
+    pending_total_sui_withdraw: source.pending_total_sui_withdraw,
+  }
+}
+
+pub fn convert_sui_system_state_summary(source: &SuiSystemStateSummary) -> pb::SuiSystemStateSummary {
+  pb::SuiSystemStateSummary {
+    epoch: source.epoch,
+    protocol_version: source.protocol_version,
+    system_state_version: source.system_state_version,
+    storage_fund_total_object_storage_rebates: source.storage_fund_total_object_storage_rebates,
+    storage_fund_non_refundable_balance: source.storage_fund_non_refundable_balance,
+    reference_gas_price: source.reference_gas_price,
+    safe_mode: source.safe_mode,
+    epoch_start_timestamp_ms: source.epoch_start_timestamp_ms,
+    epoch_duration_ms: source.epoch_duration_ms,
+    stake_subsidy_balance: source.stake_subsidy_balance,
+    total_stake: source.total_stake,
+    active_validators: source.active_validators.iter().map(convert_sui_validator_summary).collect(),
+  }
+}
@@ -1,7 +1,7 @@
-use sui_json_rpc_types::{SuiTransactionBlockKind, SuiCallArg, SuiCommand, SuiObjectArg};
+use sui_json_rpc_types::{SuiTransactionBlockKind, SuiCallArg, SuiCommand, SuiObjectArg, SuiChangeEpoch};
 use crate::pb::sui::checkpoint as pb;
 use super::common::{
-  convert_sui_object, convert_type_tag, convert_sui_json_value, convert_sui_argument
+  convert_sui_object, convert_type_tag, convert_type_tag_str, convert_sui_json_value, convert_sui_argument
 };
 
 pub fn convert_sui_call_arg(source: &SuiCallArg) -> pb::SuiCallArg {
@@ -41,6 +41,7 @@ pub fn convert_sui_command(source: &SuiCommand) -> pb::SuiCommand {
       function: source.function.to_string(),
       type_arguments: source.type_arguments.clone(),
       arguments: source.arguments.iter().map(convert_sui_argument).collect(),
+      parsed_type_arguments: source.type_arguments.iter().map(|t| convert_type_tag_str(t)).collect(),
     }),
     SuiCommand::TransferObjects(val1, val2) => pb::sui_command::SuiCommand::TransferObjects(pb::TransferObjectsPair {
       one: val1.iter().map(convert_sui_argument).collect(),
@@ -67,6 +68,7 @@ pub fn convert_sui_command(source: &SuiCommand) -> pb::SuiCommand {
     SuiCommand::MakeMoveVec(val1, val2) => pb::sui_command::SuiCommand::MakeMoveVec(pb::MakeMoveVecPair {
       one: val1.clone(),
       two: val2.iter().map(convert_sui_argument).collect(),
+      parsed_one: val1.as_ref().map(|t| convert_type_tag_str(t)),
     }),
   };
 
@@ -75,16 +77,20 @@ pub fn convert_sui_command(source: &SuiCommand) -> pb::SuiCommand {
   }
 }
 
+pub fn convert_sui_change_epoch(source: &SuiChangeEpoch) -> pb::SuiChangeEpoch {
+  pb::SuiChangeEpoch {
+    epoch: source.epoch,
+    storage_charge: source.storage_charge,
+    computation_charge: source.computation_charge,
+    storage_rebate: source.storage_rebate,
+    epoch_start_timestamp_ms: source.epoch_start_timestamp_ms,
+  }
+}
+
 pub fn convert_sui_transaction_block_kind(source: &SuiTransactionBlockKind) -> Option<pb::SuiTransactionBlockKind> {
   let sui_transaction_block_kind = match source {
     SuiTransactionBlockKind::ChangeEpoch(source) => pb::sui_transaction_block_kind::SuiTransactionBlockKind::ChangeEpoch(
-      pb::SuiChangeEpoch {
-        epoch: source.epoch,
-        storage_charge: source.storage_charge,
-        computation_charge: source.computation_charge,
-        storage_rebate: source.storage_rebate,
-        epoch_start_timestamp_ms: source.epoch_start_timestamp_ms,
-      }
+      convert_sui_change_epoch(source)
     ),
     SuiTransactionBlockKind::Genesis(source) => pb::sui_transaction_block_kind::SuiTransactionBlockKind::Genesis(
       pb::SuiGenesisTransaction {
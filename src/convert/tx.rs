@@ -14,5 +14,7 @@ pub fn convert_transaction(source: &CheckpointTransactionBlockResponse) -> pb::C
     timestamp_ms: source.timestamp_ms,
     confirmed_local_execution: source.confirmed_local_execution,
     checkpoint: source.checkpoint,
+    // Set by the filter when the streamer runs with watched addresses
+    address_matches: vec![],
   }
 }
@@ -0,0 +1,49 @@
+use std::io::{self, Write};
+use clap::ValueEnum;
+use eyre::{Result, Report};
+use prost::Message;
+use crate::{config::Config, position::CheckpointPosition, runtime::FirehoseStreamer};
+
+/// How the `fetch` subcommand prints the checkpoint
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum FetchFormat {
+  /// The converted `pb::CheckpointData` as pretty printed JSON
+  Json,
+  /// The converted `pb::CheckpointData` as raw protobuf bytes
+  Protobuf,
+  /// The exact FIRE lines the streamer would emit for this checkpoint
+  Fire,
+}
+
+/// Downloads a single checkpoint, converts it with the configured filter and output profile, and prints it to
+/// stdout. Neither the cursor file nor the chain id are used, so it can be pointed at any checkpoint of any node.
+pub async fn fetch(rpc_client_url: String, config: &Config, seq: u64, format: FetchFormat) -> Result<()> {
+  let config = Config {
+    starting_checkpoint_seq: CheckpointPosition::Seq(seq),
+    stop_checkpoint_seq: Some(CheckpointPosition::Seq(seq)),
+    cursor_path: None,
+    ..config.clone()
+  };
+  let mut firehose_streamer = FirehoseStreamer::new(rpc_client_url, &config);
+  firehose_streamer.connect().await?;
+
+  match format {
+    FetchFormat::Json => {
+      let checkpoint_data = firehose_streamer.fetch_checkpoint_data().await?;
+      let json = serde_json::to_string_pretty(&checkpoint_data).map_err(|e| {
+        Report::msg(format!("Failed to serialize checkpoint {}: {}", seq, e))
+      })?;
+
+      println!("{}", json);
+    }
+    FetchFormat::Protobuf => {
+      let checkpoint_data = firehose_streamer.fetch_checkpoint_data().await?;
+      io::stdout().write_all(&checkpoint_data.encode_to_vec())?;
+    }
+    FetchFormat::Fire => firehose_streamer.convert_next_block().await?,
+  }
+
+  io::stdout().flush()?;
+
+  Ok(())
+}
@@ -0,0 +1,115 @@
+use std::collections::BTreeMap;
+use crate::pb::sui::checkpoint as pb;
+use super::rules::normalize_address;
+
+type Reason = pb::AddressMatchReason;
+
+/// Finds the transactions that involve any of the watched addresses.
+#[derive(Debug, Clone, Default)]
+pub struct AddressWatcher {
+  addresses: Vec<String>,
+}
+
+impl AddressWatcher {
+  pub fn new(addresses: Vec<String>) -> Self {
+    Self {
+      addresses: addresses.iter().map(|a| normalize_address(a)).collect(),
+    }
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.addresses.is_empty()
+  }
+
+  /// Returns the watched addresses the transaction involves along with the reasons why. An empty result
+  /// means that the transaction does not involve any of the watched addresses.
+  pub fn match_transaction(&self, source: &pb::CheckpointTransactionBlockResponse) -> Vec<pb::AddressMatch> {
+    let mut matches = BTreeMap::<String, Vec<i32>>::new();
+    let mut add = |address: &str, reason: Reason| {
+      let address = normalize_address(address);
+
+      if self.addresses.contains(&address) {
+        let reasons = matches.entry(address).or_default();
+        if !reasons.contains(&(reason as i32)) {
+          reasons.push(reason as i32);
+        }
+      }
+    };
+
+    if let Some(data) = transaction_data(source) {
+      add(&data.sender, Reason::Sender);
+
+      if let Some(gas_data) = &data.gas_data {
+        add(&gas_data.owner, Reason::GasOwner);
+      }
+
+      for recipient in transfer_recipients(data) {
+        add(recipient, Reason::TransferRecipient);
+      }
+    }
+
+    if let Some(effects) = transaction_effects(source) {
+      for owner in effects.created.iter().filter_map(address_owner) {
+        add(owner, Reason::CreatedObjectOwner);
+      }
+
+      for owner in effects.mutated.iter().filter_map(address_owner) {
+        add(owner, Reason::MutatedObjectOwner);
+      }
+    }
+
+    matches
+    .into_iter()
+    .map(|(address, reasons)| pb::AddressMatch {address, reasons})
+    .collect()
+  }
+}
+
+pub(super) fn transaction_data(source: &pb::CheckpointTransactionBlockResponse) -> Option<&pb::SuiTransactionBlockDataV1> {
+  let data = source.transaction.as_ref()?.data.as_ref()?.sui_transaction_block_data.as_ref()?;
+  let pb::sui_transaction_block_data::SuiTransactionBlockData::V1(data) = data;
+
+  Some(data)
+}
+
+pub(super) fn transaction_effects(source: &pb::CheckpointTransactionBlockResponse) -> Option<&pb::SuiTransactionBlockEffectsV1> {
+  let effects = source.effects.as_ref()?.sui_transaction_block_effects.as_ref()?;
+  let pb::sui_transaction_block_effects::SuiTransactionBlockEffects::V1(effects) = effects;
+
+  Some(effects)
+}
+
+fn address_owner(source: &pb::OwnedObjectRef) -> Option<&str> {
+  match source.owner.as_ref()?.owner.as_ref()? {
+    pb::owner::Owner::AddressOwner(address) => Some(address),
+    _ => None,
+  }
+}
+
+/// The recipients of all the `TransferObjects` commands. The recipient is an argument that refers to one of the
+/// pure inputs of the programmable transaction.
+fn transfer_recipients(source: &pb::SuiTransactionBlockDataV1) -> Vec<&str> {
+  let ptb = match source.transaction.as_ref().and_then(|k| k.sui_transaction_block_kind.as_ref()) {
+    Some(pb::sui_transaction_block_kind::SuiTransactionBlockKind::ProgrammableTransaction(ptb)) => ptb,
+    _ => return vec![],
+  };
+
+  ptb.commands
+  .iter()
+  .filter_map(|command| match command.sui_command.as_ref()? {
+    pb::sui_command::SuiCommand::TransferObjects(transfer) => transfer.two.as_ref(),
+    _ => None,
+  })
+  .filter_map(|recipient| match recipient.sui_arguments.as_ref()? {
+    pb::sui_argument::SuiArguments::Input(index) => ptb.inputs.get(*index as usize),
+    _ => None,
+  })
+  .filter_map(|input| match input.sui_call_arg.as_ref()? {
+    pb::sui_call_arg::SuiCallArg::Pure(pure) => match pure.value.as_ref()?.value.as_ref()? {
+      pb::sui_json_value::Value::String(address) => Some(address.as_str()),
+      _ => None,
+    },
+    _ => None,
+  })
+  .collect()
+}
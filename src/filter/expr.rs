@@ -0,0 +1,662 @@
+//! A small expression language to filter transactions, i.e.
+//!
+//! `sender == 0x2a && move_call("0x2::coin::*") && status == success`
+//!
+//! Expressions combine the following terms with `&&`, `||`, `!` and parentheses. `&&` binds tighter than `||`.
+//!
+//! - `sender` and `gas_owner` compared with `==` or `!=` against an address i.e. `sender != 0x2a`
+//! - `status` compared with `==` or `!=` against `success` or `failure`
+//! - `kind` compared with `==` or `!=` against `programmable`, `change_epoch`, `genesis` or
+//!   `consensus_commit_prologue`
+//! - `digest` compared with `==` or `!=` against a string i.e. `digest == "6Xq..."`. Strings can contain `\"` and
+//!   `\\` escapes
+//! - `checkpoint`, `timestamp_ms`, `gas_budget` and `gas_price` compared with `==`, `!=`, `<`, `<=`, `>` or `>=`
+//!   against a number
+//! - `move_call("<package>::<module>::<function>")` is true if the transaction calls a matching function. Trailing
+//!   parts can be omitted and any part can be `*` i.e. `move_call("0x2::coin::*")` or `move_call("0x2")`
+//! - `event("<address>::<module>::<name>")` is true if the transaction emits an event of a matching type, with the
+//!   same wildcard rules as `move_call`
+//! - `involves(<address>)` is true if the address is the sender, the gas owner, the owner of a created or mutated
+//!   object or the recipient of a transfer
+use std::{fmt, str::FromStr};
+use serde::{Serialize, Deserialize};
+use crate::pb::sui::checkpoint as pb;
+use super::{
+  address::{AddressWatcher, transaction_data, transaction_effects},
+  rules::{move_calls, normalize_address, parse_address},
+};
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+  LParen,
+  RParen,
+  And,
+  Or,
+  Not,
+  Op(Op),
+  Ident(String),
+  Number(u64),
+  Address(String),
+  Str(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Op {
+  Eq,
+  Ne,
+  Lt,
+  Le,
+  Gt,
+  Ge,
+}
+
+impl Op {
+  fn compare<T: PartialOrd>(&self, left: T, right: T) -> bool {
+    match self {
+      Op::Eq => left == right,
+      Op::Ne => left != right,
+      Op::Lt => left < right,
+      Op::Le => left <= right,
+      Op::Gt => left > right,
+      Op::Ge => left >= right,
+    }
+  }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum AddressField {
+  Sender,
+  GasOwner,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum NumberField {
+  Checkpoint,
+  TimestampMs,
+  GasBudget,
+  GasPrice,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Kind {
+  Programmable,
+  ChangeEpoch,
+  Genesis,
+  ConsensusCommitPrologue,
+}
+
+/// A `<address>::<module>::<name>` pattern where `None` matches anything
+#[derive(Debug, Clone, PartialEq)]
+struct Pattern {
+  address: Option<String>,
+  module: Option<String>,
+  name: Option<String>,
+}
+
+impl Pattern {
+  fn matches(&self, address: &str, module: &str, name: &str) -> bool {
+    self.address.as_ref().map(|a| *a == normalize_address(address)).unwrap_or(true)
+    && self.module.as_ref().map(|m| m == module).unwrap_or(true)
+    && self.name.as_ref().map(|n| n == name).unwrap_or(true)
+  }
+}
+
+#[derive(Debug, Clone)]
+enum Expr {
+  And(Box<Expr>, Box<Expr>),
+  Or(Box<Expr>, Box<Expr>),
+  Not(Box<Expr>),
+  Address(AddressField, Op, String),
+  Number(NumberField, Op, u64),
+  Status(Op, bool),
+  Kind(Op, Kind),
+  Digest(Op, String),
+  MoveCall(Pattern),
+  Event(Pattern),
+  Involves(AddressWatcher),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseError {
+  position: usize,
+  message: String,
+}
+
+impl fmt::Display for ParseError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "invalid filter expression at position {}: {}", self.position, self.message)
+  }
+}
+
+fn error<T>(position: usize, message: impl Into<String>) -> Result<T, ParseError> {
+  Err(ParseError {position, message: message.into()})
+}
+
+fn tokenize(source: &str) -> Result<Vec<(usize, Token)>, ParseError> {
+  let chars = source.char_indices().collect::<Vec<_>>();
+  let mut tokens = vec![];
+  let mut i = 0;
+
+  while i < chars.len() {
+    let (position, c) = chars[i];
+    let next = chars.get(i + 1).map(|(_, c)| *c);
+
+    let (token, length) = match (c, next) {
+      (c, _) if c.is_whitespace() => {
+        i += 1;
+        continue
+      },
+      ('(', _) => (Token::LParen, 1),
+      (')', _) => (Token::RParen, 1),
+      ('&', Some('&')) => (Token::And, 2),
+      ('|', Some('|')) => (Token::Or, 2),
+      ('=', Some('=')) => (Token::Op(Op::Eq), 2),
+      ('!', Some('=')) => (Token::Op(Op::Ne), 2),
+      ('<', Some('=')) => (Token::Op(Op::Le), 2),
+      ('>', Some('=')) => (Token::Op(Op::Ge), 2),
+      ('!', _) => (Token::Not, 1),
+      ('<', _) => (Token::Op(Op::Lt), 1),
+      ('>', _) => (Token::Op(Op::Gt), 1),
+      ('"', _) => {
+        let mut value = String::new();
+        let mut end = i + 1;
+
+        loop {
+          match chars.get(end).map(|(_, c)| *c) {
+            Some('"') => break,
+            Some('\\') => match chars.get(end + 1) {
+              Some((_, c @ ('"' | '\\'))) => {
+                value.push(*c);
+                end += 2;
+              },
+              Some((p, c)) => return error(*p, format!("invalid escape '\\{}', expected \\\" or \\\\", c)),
+              None => return error(position, "unterminated string"),
+            },
+            Some(c) => {
+              value.push(c);
+              end += 1;
+            },
+            None => return error(position, "unterminated string"),
+          }
+        }
+
+        (Token::Str(value), end - i + 1)
+      },
+      (c, _) if c.is_ascii_alphanumeric() || c == '_' => {
+        let length = chars[i..].iter().take_while(|(_, c)| c.is_ascii_alphanumeric() || *c == '_').count();
+        let word = chars[i..i + length].iter().map(|(_, c)| c).collect::<String>();
+
+        let token = if word.starts_with("0x") {
+          Token::Address(parse_address(&word).or_else(|e| error(position, e))?)
+        } else if word.chars().all(|c| c.is_ascii_digit()) {
+          Token::Number(word.parse().or_else(|_| error(position, format!("'{}' is not a valid number", word)))?)
+        } else if word.chars().next().is_some_and(|c| c.is_ascii_digit()) {
+          return error(position, format!("'{}' is not a valid number", word))
+        } else {
+          Token::Ident(word)
+        };
+
+        (token, length)
+      },
+      (c, _) => return error(position, format!("unexpected character '{}'", c)),
+    };
+
+    tokens.push((position, token));
+    i += length;
+  }
+
+  Ok(tokens)
+}
+
+struct Parser {
+  tokens: Vec<(usize, Token)>,
+  index: usize,
+  end: usize,
+}
+
+impl Parser {
+  fn position(&self) -> usize {
+    self.tokens.get(self.index).map(|(p, _)| *p).unwrap_or(self.end)
+  }
+
+  fn peek(&self) -> Option<&Token> {
+    self.tokens.get(self.index).map(|(_, t)| t)
+  }
+
+  fn next(&mut self) -> Option<Token> {
+    let token = self.tokens.get(self.index).map(|(_, t)| t.clone());
+    self.index += 1;
+    token
+  }
+
+  fn expect(&mut self, expected: Token, description: &str) -> Result<(), ParseError> {
+    let position = self.position();
+
+    match self.next() {
+      Some(token) if token == expected => Ok(()),
+      _ => error(position, format!("expected {}", description)),
+    }
+  }
+
+  fn parse_or(&mut self) -> Result<Expr, ParseError> {
+    let mut expr = self.parse_and()?;
+
+    while self.peek() == Some(&Token::Or) {
+      self.next();
+      expr = Expr::Or(Box::new(expr), Box::new(self.parse_and()?));
+    }
+
+    Ok(expr)
+  }
+
+  fn parse_and(&mut self) -> Result<Expr, ParseError> {
+    let mut expr = self.parse_unary()?;
+
+    while self.peek() == Some(&Token::And) {
+      self.next();
+      expr = Expr::And(Box::new(expr), Box::new(self.parse_unary()?));
+    }
+
+    Ok(expr)
+  }
+
+  fn parse_unary(&mut self) -> Result<Expr, ParseError> {
+    if self.peek() == Some(&Token::Not) {
+      self.next();
+      return Ok(Expr::Not(Box::new(self.parse_unary()?)))
+    }
+
+    self.parse_primary()
+  }
+
+  fn parse_primary(&mut self) -> Result<Expr, ParseError> {
+    let position = self.position();
+
+    match self.next() {
+      Some(Token::LParen) => {
+        let expr = self.parse_or()?;
+        self.expect(Token::RParen, "')'")?;
+        Ok(expr)
+      },
+      Some(Token::Ident(name)) if self.peek() == Some(&Token::LParen) => {
+        self.next();
+        let expr = self.parse_call(position, &name)?;
+        self.expect(Token::RParen, "')'")?;
+        Ok(expr)
+      },
+      Some(Token::Ident(name)) => self.parse_comparison(position, &name),
+      _ => error(position, "expected a comparison, a function call or '('"),
+    }
+  }
+
+  fn parse_call(&mut self, position: usize, name: &str) -> Result<Expr, ParseError> {
+    let argument_position = self.position();
+
+    match (name, self.next()) {
+      ("move_call", Some(Token::Str(pattern))) => Ok(Expr::MoveCall(parse_pattern(argument_position, &pattern)?)),
+      ("event", Some(Token::Str(pattern))) => Ok(Expr::Event(parse_pattern(argument_position, &pattern)?)),
+      ("involves", Some(Token::Address(address))) => Ok(Expr::Involves(AddressWatcher::new(vec![address]))),
+      ("move_call" | "event", _) => error(argument_position, "expected a quoted pattern i.e. \"0x2::coin::*\""),
+      ("involves", _) => error(argument_position, "expected an address"),
+      _ => error(position, format!("unknown function '{}', expected move_call, event or involves", name)),
+    }
+  }
+
+  fn parse_comparison(&mut self, position: usize, field: &str) -> Result<Expr, ParseError> {
+    let op_position = self.position();
+    let op = match self.next() {
+      Some(Token::Op(op)) => op,
+      _ => return error(op_position, format!("expected a comparison operator after '{}'", field)),
+    };
+
+    let value_position = self.position();
+    let value = self.next();
+    let equality = |op: Op| match op {
+      Op::Eq | Op::Ne => Ok(op),
+      _ => error(op_position, format!("'{}' can only be compared with == or !=", field)),
+    };
+
+    let address_field = match field {
+      "sender" => Some(AddressField::Sender),
+      "gas_owner" => Some(AddressField::GasOwner),
+      _ => None,
+    };
+    let number_field = match field {
+      "checkpoint" => Some(NumberField::Checkpoint),
+      "timestamp_ms" => Some(NumberField::TimestampMs),
+      "gas_budget" => Some(NumberField::GasBudget),
+      "gas_price" => Some(NumberField::GasPrice),
+      _ => None,
+    };
+
+    if let Some(address_field) = address_field {
+      return match value {
+        Some(Token::Address(address)) => Ok(Expr::Address(address_field, equality(op)?, address)),
+        _ => error(value_position, "expected an address"),
+      }
+    }
+
+    if let Some(number_field) = number_field {
+      return match value {
+        Some(Token::Number(number)) => Ok(Expr::Number(number_field, op, number)),
+        _ => error(value_position, "expected a number"),
+      }
+    }
+
+    match (field, value) {
+      ("status", Some(Token::Ident(status))) => match status.as_str() {
+        "success" => Ok(Expr::Status(equality(op)?, true)),
+        "failure" => Ok(Expr::Status(equality(op)?, false)),
+        _ => error(value_position, "expected success or failure"),
+      },
+      ("status", _) => error(value_position, "expected success or failure"),
+      ("kind", Some(Token::Ident(kind))) => {
+        let kind = match kind.as_str() {
+          "programmable" => Kind::Programmable,
+          "change_epoch" => Kind::ChangeEpoch,
+          "genesis" => Kind::Genesis,
+          "consensus_commit_prologue" => Kind::ConsensusCommitPrologue,
+          _ => return error(
+            value_position,
+            "expected programmable, change_epoch, genesis or consensus_commit_prologue",
+          ),
+        };
+
+        Ok(Expr::Kind(equality(op)?, kind))
+      },
+      ("kind", _) => error(value_position, "expected programmable, change_epoch, genesis or consensus_commit_prologue"),
+      ("digest", Some(Token::Str(digest))) => Ok(Expr::Digest(equality(op)?, digest)),
+      ("digest", _) => error(value_position, "expected a quoted digest"),
+      _ => error(position, format!(
+        "unknown field '{}', expected one of sender, gas_owner, status, kind, digest, checkpoint, timestamp_ms, \
+        gas_budget or gas_price",
+        field,
+      )),
+    }
+  }
+}
+
+fn parse_pattern(position: usize, source: &str) -> Result<Pattern, ParseError> {
+  let parts = source.split("::").map(|p| (p != "*").then(|| p.to_string())).collect::<Vec<_>>();
+  if parts.is_empty() || parts.len() > 3 || parts.iter().flatten().any(|p| p.is_empty()) {
+    return error(position, format!("'{}' is not a valid pattern, expected <address>::<module>::<name>", source))
+  }
+
+  let address = match &parts[0] {
+    Some(address) => Some(parse_address(address).or_else(|e| error(position, e))?),
+    None => None,
+  };
+
+  Ok(Pattern {
+    address,
+    module: parts.get(1).cloned().flatten(),
+    name: parts.get(2).cloned().flatten(),
+  })
+}
+
+impl Expr {
+  fn eval(&self, source: &pb::CheckpointTransactionBlockResponse) -> bool {
+    match self {
+      Expr::And(left, right) => left.eval(source) && right.eval(source),
+      Expr::Or(left, right) => left.eval(source) || right.eval(source),
+      Expr::Not(expr) => !expr.eval(source),
+      Expr::Address(field, op, address) => {
+        let value = transaction_data(source).map(|data| match field {
+          AddressField::Sender => data.sender.as_str(),
+          AddressField::GasOwner => data.gas_data.as_ref().map(|g| g.owner.as_str()).unwrap_or_default(),
+        });
+
+        value.is_some_and(|value| op.compare(normalize_address(value).as_str(), address))
+      },
+      Expr::Number(field, op, number) => {
+        let gas_data = transaction_data(source).and_then(|data| data.gas_data.as_ref());
+        let value = match field {
+          NumberField::Checkpoint => Some(source.checkpoint),
+          NumberField::TimestampMs => Some(source.timestamp_ms),
+          NumberField::GasBudget => gas_data.map(|g| g.budget),
+          NumberField::GasPrice => gas_data.map(|g| g.price),
+        };
+
+        value.is_some_and(|value| op.compare(value, *number))
+      },
+      Expr::Status(op, success) => {
+        let status = transaction_effects(source)
+        .and_then(|effects| effects.status.as_ref())
+        .and_then(|status| status.sui_execution_status.as_ref())
+        .map(|status| matches!(status, pb::sui_execution_status::SuiExecutionStatus::Success(_)));
+
+        status.is_some_and(|status| op.compare(status, *success))
+      },
+      Expr::Kind(op, kind) => {
+        use pb::sui_transaction_block_kind::SuiTransactionBlockKind;
+
+        let value = transaction_data(source)
+        .and_then(|data| data.transaction.as_ref())
+        .and_then(|kind| kind.sui_transaction_block_kind.as_ref())
+        .map(|kind| match kind {
+          SuiTransactionBlockKind::ProgrammableTransaction(_) => Kind::Programmable,
+          SuiTransactionBlockKind::ChangeEpoch(_) => Kind::ChangeEpoch,
+          SuiTransactionBlockKind::Genesis(_) => Kind::Genesis,
+          SuiTransactionBlockKind::ConsensusCommitPrologue(_) => Kind::ConsensusCommitPrologue,
+        });
+
+        value.is_some_and(|value| (value == *kind) == (*op == Op::Eq))
+      },
+      Expr::Digest(op, digest) => op.compare(&source.digest, digest),
+      Expr::MoveCall(pattern) => move_calls(source).any(|move_call| {
+        let package = move_call.package.as_ref().map(|p| p.account_address.as_str()).unwrap_or_default();
+        pattern.matches(package, &move_call.module, &move_call.function)
+      }),
+      Expr::Event(pattern) => source.events.as_ref().is_some_and(|events| {
+        events.data.iter().filter_map(|event| event.r#type.as_ref()).any(|t| pattern.matches(&t.address, &t.module, &t.name))
+      }),
+      Expr::Involves(watcher) => !watcher.match_transaction(source).is_empty(),
+    }
+  }
+}
+
+/// A parsed filter expression. See the module documentation for the syntax.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct FilterExpr {
+  source: String,
+  expr: Expr,
+}
+
+impl FilterExpr {
+  pub fn parse(source: &str) -> Result<Self, ParseError> {
+    let tokens = tokenize(source)?;
+    if tokens.is_empty() {
+      return error(0, "the expression is empty")
+    }
+
+    let mut parser = Parser {tokens, index: 0, end: source.len()};
+    let expr = parser.parse_or()?;
+
+    if parser.peek().is_some() {
+      return error(parser.position(), "expected && or || or the end of the expression")
+    }
+
+    Ok(Self {
+      source: source.to_string(),
+      expr,
+    })
+  }
+
+  pub fn matches(&self, source: &pb::CheckpointTransactionBlockResponse) -> bool {
+    self.expr.eval(source)
+  }
+}
+
+impl FromStr for FilterExpr {
+  type Err = String;
+
+  fn from_str(source: &str) -> Result<Self, Self::Err> {
+    Self::parse(source).map_err(|e| e.to_string())
+  }
+}
+
+impl TryFrom<String> for FilterExpr {
+  type Error = String;
+
+  fn try_from(source: String) -> Result<Self, Self::Error> {
+    source.parse()
+  }
+}
+
+impl fmt::Display for FilterExpr {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{}", self.source)
+  }
+}
+
+impl From<FilterExpr> for String {
+  fn from(source: FilterExpr) -> Self {
+    source.source
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use pb::{
+    sui_command::SuiCommand, sui_transaction_block_data::SuiTransactionBlockData,
+    sui_transaction_block_kind::SuiTransactionBlockKind,
+  };
+
+  fn transaction(checkpoint: u64, move_call: (&str, &str, &str)) -> pb::CheckpointTransactionBlockResponse {
+    let (package, module, function) = move_call;
+    let move_call = pb::SuiProgrammableMoveCall {
+      package: Some(pb::ObjectId {account_address: package.to_string()}),
+      module: module.to_string(),
+      function: function.to_string(),
+      ..Default::default()
+    };
+    let kind = SuiTransactionBlockKind::ProgrammableTransaction(pb::SuiProgrammableTransactionBlock {
+      inputs: vec![],
+      commands: vec![pb::SuiCommand {sui_command: Some(SuiCommand::MoveCall(move_call))}],
+    });
+
+    pb::CheckpointTransactionBlockResponse {
+      digest: "6Xq\"\\".to_string(),
+      checkpoint,
+      transaction: Some(pb::SuiTransactionBlock {
+        data: Some(pb::SuiTransactionBlockData {
+          sui_transaction_block_data: Some(SuiTransactionBlockData::V1(pb::SuiTransactionBlockDataV1 {
+            transaction: Some(pb::SuiTransactionBlockKind {sui_transaction_block_kind: Some(kind)}),
+            sender: "0x2a".to_string(),
+            ..Default::default()
+          })),
+        }),
+      }),
+      ..Default::default()
+    }
+  }
+
+  fn matches(source: &str, checkpoint: u64) -> bool {
+    FilterExpr::parse(source)
+    .unwrap_or_else(|e| panic!("{}", e))
+    .matches(&transaction(checkpoint, ("0x2", "coin", "split")))
+  }
+
+  fn parse_error(source: &str) -> String {
+    FilterExpr::parse(source).expect_err("the expression should not parse").to_string()
+  }
+
+  #[test]
+  fn and_binds_tighter_than_or() {
+    assert!(matches("checkpoint == 1 || checkpoint == 2 && checkpoint == 3", 1));
+    assert!(!matches("checkpoint == 2 && checkpoint == 3 || checkpoint == 4", 1));
+    assert!(matches("checkpoint == 2 && checkpoint == 3 || checkpoint == 1", 1));
+  }
+
+  #[test]
+  fn parentheses_override_precedence() {
+    assert!(!matches("(checkpoint == 1 || checkpoint == 2) && checkpoint == 3", 1));
+    assert!(matches("((checkpoint == 1))", 1));
+  }
+
+  #[test]
+  fn not_applies_to_the_next_term() {
+    assert!(!matches("!checkpoint == 1", 1));
+    assert!(matches("!checkpoint == 1 || checkpoint == 1", 1));
+    assert!(!matches("!(checkpoint == 1 || checkpoint == 2)", 1));
+    assert!(matches("!!checkpoint == 1", 1));
+    assert!(matches("checkpoint != 2 && sender != 0x2b", 1));
+  }
+
+  #[test]
+  fn comparisons() {
+    assert!(matches("checkpoint < 2 && checkpoint <= 1 && checkpoint > 0 && checkpoint >= 1", 1));
+    assert!(matches("sender == 0x000000000000000000000000000000000000002a", 1));
+    assert!(!matches("sender == 0x2b", 1));
+    assert!(matches("kind == programmable && kind != genesis", 1));
+    // Fields that are missing from the transaction never match
+    assert!(!matches("status == success", 1));
+    assert!(!matches("status != success", 1));
+  }
+
+  #[test]
+  fn strings_support_escapes() {
+    assert!(matches(r#"digest == "6Xq\"\\""#, 1));
+    assert!(!matches(r#"digest == "6Xq\"""#, 1));
+  }
+
+  #[test]
+  fn move_call_patterns() {
+    assert!(matches(r#"move_call("0x2::coin::split")"#, 1));
+    assert!(matches(r#"move_call("0x2::coin::*")"#, 1));
+    assert!(matches(r#"move_call("0x2::*::split")"#, 1));
+    assert!(matches(r#"move_call("*::coin")"#, 1));
+    assert!(matches(r#"move_call("0x0000000000000000000000000000000000000002")"#, 1));
+    assert!(!matches(r#"move_call("0x2::coin::join")"#, 1));
+    assert!(!matches(r#"move_call("0x3::*::*")"#, 1));
+  }
+
+  #[test]
+  fn error_messages() {
+    assert_eq!(parse_error(""), "invalid filter expression at position 0: the expression is empty");
+    assert_eq!(
+      parse_error("checkpoint == 1 &&"),
+      "invalid filter expression at position 18: expected a comparison, a function call or '('",
+    );
+    assert_eq!(parse_error("(checkpoint == 1"), "invalid filter expression at position 16: expected ')'");
+    assert_eq!(
+      parse_error("checkpoint == 1 checkpoint == 2"),
+      "invalid filter expression at position 16: expected && or || or the end of the expression",
+    );
+    assert_eq!(parse_error(r#"digest == "6Xq"#), "invalid filter expression at position 10: unterminated string");
+    assert_eq!(
+      parse_error(r#"digest == "6Xq\n""#),
+      r#"invalid filter expression at position 15: invalid escape '\n', expected \" or \\"#,
+    );
+    assert_eq!(parse_error("checkpoint = 1"), "invalid filter expression at position 11: unexpected character '='");
+    assert_eq!(parse_error("checkpoint == 1x"), "invalid filter expression at position 14: '1x' is not a valid number");
+    assert_eq!(
+      parse_error("status > success"),
+      "invalid filter expression at position 7: 'status' can only be compared with == or !=",
+    );
+    assert_eq!(
+      parse_error("owner == 0x2"),
+      "invalid filter expression at position 0: unknown field 'owner', expected one of sender, gas_owner, status, \
+      kind, digest, checkpoint, timestamp_ms, gas_budget or gas_price",
+    );
+    assert_eq!(
+      parse_error("calls(\"0x2\")"),
+      "invalid filter expression at position 0: unknown function 'calls', expected move_call, event or involves",
+    );
+    assert_eq!(
+      parse_error("move_call(0x2)"),
+      "invalid filter expression at position 10: expected a quoted pattern i.e. \"0x2::coin::*\"",
+    );
+    assert_eq!(
+      parse_error(r#"move_call("0x2::coin::split::x")"#),
+      "invalid filter expression at position 10: '0x2::coin::split::x' is not a valid pattern, expected \
+      <address>::<module>::<name>",
+    );
+    assert_eq!(
+      parse_error(r#"move_call("0xzz")"#),
+      "invalid filter expression at position 10: '0xzz' is not a valid address",
+    );
+  }
+}
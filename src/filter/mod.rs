@@ -0,0 +1,169 @@
+pub mod rules;
+pub mod address;
+pub mod expr;
+
+use std::collections::HashSet;
+use crate::pb::sui::checkpoint as pb;
+use self::{rules::FilterRules, address::AddressWatcher, expr::FilterExpr};
+
+/// Decides which of the converted transactions and changed objects are emitted. Checkpoint boundaries are
+/// always emitted so that cursors stay continuous, even if everything in the checkpoint is filtered out.
+#[derive(Debug, Clone, Default)]
+pub struct Filter {
+  include: FilterRules,
+  exclude: FilterRules,
+  addresses: AddressWatcher,
+  expression: Option<FilterExpr>,
+}
+
+impl Filter {
+  pub fn new(
+    include: FilterRules,
+    exclude: FilterRules,
+    addresses: AddressWatcher,
+    expression: Option<FilterExpr>,
+  ) -> Self {
+    Self {include, exclude, addresses, expression}
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.include.is_empty() && self.exclude.is_empty() && self.addresses.is_empty() && self.expression.is_none()
+  }
+
+  /// A transaction is kept if it matches any of the include rules (or there are none), none of the exclude
+  /// rules and the filter expression if there is one.
+  pub fn keep_transaction(&self, source: &pb::CheckpointTransactionBlockResponse) -> bool {
+    (self.include.is_empty() || self.include.matches_transaction(source))
+    && !self.exclude.matches_transaction(source)
+    && self.expression.as_ref().map(|e| e.matches(source)).unwrap_or(true)
+  }
+
+  /// An object is kept if it was changed by one of the kept transactions or it matches any of the include
+  /// rules, and it does not match any of the exclude rules. When watching addresses or filtering with an
+  /// expression, only the objects changed by the kept transactions are kept.
+  pub fn keep_object(&self, source: &pb::ChangedObject, kept_transactions: &HashSet<&str>) -> bool {
+    let changed_by_kept_transaction = source.data.as_ref()
+    .and_then(|d| d.previous_transaction.as_deref())
+    .is_some_and(|digest| kept_transactions.contains(digest));
+
+    let included = changed_by_kept_transaction || (
+      self.addresses.is_empty() && self.expression.is_none() && self.include.matches_object(source)
+    );
+
+    included && !self.exclude.matches_object(source)
+  }
+
+  pub fn apply(
+    &self,
+    transactions: Vec<pb::CheckpointTransactionBlockResponse>,
+    changed_objects: Vec<pb::ChangedObject>,
+  ) -> (Vec<pb::CheckpointTransactionBlockResponse>, Vec<pb::ChangedObject>) {
+    if self.is_empty() {
+      return (transactions, changed_objects)
+    }
+
+    let transactions = transactions
+    .into_iter()
+    .filter(|tx| self.keep_transaction(tx))
+    .filter_map(|mut tx| {
+      if self.addresses.is_empty() {
+        return Some(tx)
+      }
+
+      // Tag the transaction with the watched addresses it involves, or drop it if there are none
+      tx.address_matches = self.addresses.match_transaction(&tx);
+      (!tx.address_matches.is_empty()).then_some(tx)
+    })
+    .collect::<Vec<_>>();
+    let kept_transactions = transactions.iter().map(|tx| tx.digest.as_str()).collect::<HashSet<_>>();
+    let changed_objects = changed_objects
+    .into_iter()
+    .filter(|obj| self.keep_object(obj, &kept_transactions))
+    .collect();
+
+    (transactions, changed_objects)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use pb::{
+    sui_command::SuiCommand, sui_transaction_block_data::SuiTransactionBlockData,
+    sui_transaction_block_kind::SuiTransactionBlockKind,
+  };
+  use super::rules::normalize_address;
+
+  fn move_call_transaction(digest: &str, package: &str) -> pb::CheckpointTransactionBlockResponse {
+    let move_call = pb::SuiProgrammableMoveCall {
+      package: Some(pb::ObjectId {account_address: package.to_string()}),
+      module: "game".to_string(),
+      function: "play".to_string(),
+      ..Default::default()
+    };
+    let kind = SuiTransactionBlockKind::ProgrammableTransaction(pb::SuiProgrammableTransactionBlock {
+      inputs: vec![],
+      commands: vec![pb::SuiCommand {sui_command: Some(SuiCommand::MoveCall(move_call))}],
+    });
+
+    pb::CheckpointTransactionBlockResponse {
+      digest: digest.to_string(),
+      transaction: Some(pb::SuiTransactionBlock {
+        data: Some(pb::SuiTransactionBlockData {
+          sui_transaction_block_data: Some(SuiTransactionBlockData::V1(pb::SuiTransactionBlockDataV1 {
+            transaction: Some(pb::SuiTransactionBlockKind {sui_transaction_block_kind: Some(kind)}),
+            ..Default::default()
+          })),
+        }),
+      }),
+      ..Default::default()
+    }
+  }
+
+  fn changed_object(object_id: &str, previous_transaction: &str) -> pb::ChangedObject {
+    pb::ChangedObject {
+      status: None,
+      data: Some(pb::SuiObjectData {
+        object_id: Some(pb::ObjectId {account_address: object_id.to_string()}),
+        previous_transaction: Some(previous_transaction.to_string()),
+        ..Default::default()
+      }),
+    }
+  }
+
+  fn apply(filter: &Filter) -> (Vec<String>, Vec<String>) {
+    let (transactions, changed_objects) = filter.apply(
+      vec![move_call_transaction("tx-dead", "0xdead"), move_call_transaction("tx-beef", "0xbeef")],
+      vec![changed_object("0x1", "tx-dead"), changed_object("0x2", "tx-beef")],
+    );
+
+    (
+      transactions.into_iter().map(|tx| tx.digest).collect(),
+      changed_objects.into_iter().map(|obj| obj.data.unwrap().object_id.unwrap().account_address).collect(),
+    )
+  }
+
+  #[test]
+  fn exclude_rules_drop_the_objects_of_excluded_transactions() {
+    let exclude = FilterRules {packages: vec![normalize_address("0xdead")], ..Default::default()};
+    let filter = Filter::new(FilterRules::default(), exclude, AddressWatcher::default(), None);
+
+    assert_eq!(apply(&filter), (vec!["tx-beef".to_string()], vec!["0x2".to_string()]));
+  }
+
+  #[test]
+  fn include_rules_keep_the_objects_of_included_transactions() {
+    let include = FilterRules {packages: vec![normalize_address("0xdead")], ..Default::default()};
+    let filter = Filter::new(include, FilterRules::default(), AddressWatcher::default(), None);
+
+    assert_eq!(apply(&filter), (vec!["tx-dead".to_string()], vec!["0x1".to_string()]));
+  }
+
+  #[test]
+  fn include_rules_keep_matching_objects_of_other_transactions() {
+    let include = FilterRules {packages: vec![normalize_address("0x2")], ..Default::default()};
+    let filter = Filter::new(include, FilterRules::default(), AddressWatcher::default(), None);
+
+    assert_eq!(apply(&filter), (vec![], vec!["0x2".to_string()]));
+  }
+}
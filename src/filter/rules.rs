@@ -0,0 +1,364 @@
+use std::{fmt, str::FromStr};
+use serde::{Serialize, Deserialize, Deserializer, de};
+use crate::pb::sui::checkpoint as pb;
+
+const ADDRESS_LENGTH: usize = 64;
+
+/// Converts an address such as `0x2` into the zero padded, lowercase hex form used in the protobuf messages
+/// i.e. `0000000000000000000000000000000000000000000000000000000000000002`
+pub fn normalize_address(source: &str) -> String {
+  let address = source.strip_prefix("0x").unwrap_or(source).to_lowercase();
+  format!("{:0>width$}", address, width = ADDRESS_LENGTH)
+}
+
+pub fn parse_address(source: &str) -> Result<String, String> {
+  let address = source.strip_prefix("0x").unwrap_or(source);
+
+  if address.is_empty() || address.len() > ADDRESS_LENGTH || !address.chars().all(|c| c.is_ascii_hexdigit()) {
+    return Err(format!("'{}' is not a valid address", source))
+  }
+
+  Ok(normalize_address(address))
+}
+
+pub fn deserialize_addresses<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<String>, D::Error> {
+  Vec::<String>::deserialize(deserializer)?
+  .iter()
+  .map(|address| parse_address(address).map_err(de::Error::custom))
+  .collect()
+}
+
+/// Normalizes all the addresses that appear in a Move type and removes any whitespace, so that two
+/// representations of the same type can be compared as strings.
+fn normalize_type(source: &str) -> String {
+  let source = source.chars().filter(|c| !c.is_whitespace()).collect::<String>();
+  let mut normalized = String::with_capacity(source.len());
+  let mut rest = source.as_str();
+
+  while let Some(start) = rest.find("0x") {
+    normalized.push_str(&rest[..start]);
+    rest = &rest[start..];
+
+    let end = rest[2..].find(|c: char| !c.is_ascii_hexdigit()).map(|i| i + 2).unwrap_or(rest.len());
+    normalized.push_str(&normalize_address(&rest[..end]));
+    rest = &rest[end..];
+  }
+
+  normalized.push_str(rest);
+  normalized
+}
+
+pub fn format_type_tag(source: &pb::TypeTag) -> String {
+  use pb::type_tag::TypeTag;
+
+  match &source.type_tag {
+    Some(TypeTag::Bool(_)) => "bool".to_string(),
+    Some(TypeTag::U8(_)) => "u8".to_string(),
+    Some(TypeTag::U16(_)) => "u16".to_string(),
+    Some(TypeTag::U32(_)) => "u32".to_string(),
+    Some(TypeTag::U64(_)) => "u64".to_string(),
+    Some(TypeTag::U128(_)) => "u128".to_string(),
+    Some(TypeTag::U256(_)) => "u256".to_string(),
+    Some(TypeTag::Address(_)) => "address".to_string(),
+    Some(TypeTag::Signer(_)) => "signer".to_string(),
+    Some(TypeTag::Vector(type_tag)) => format!("vector<{}>", format_type_tag(type_tag)),
+    Some(TypeTag::Struct(struct_tag)) => format_struct_tag(struct_tag),
+    None => String::new(),
+  }
+}
+
+/// Formats the struct tag as `address::module::name<type_params>` using normalized addresses
+pub fn format_struct_tag(source: &pb::StructTag) -> String {
+  let type_params = source.type_params.as_ref().map(|t| t.list.as_slice()).unwrap_or_default();
+  let base = format!("{}::{}::{}", normalize_address(&source.address), source.module, source.name);
+
+  if type_params.is_empty() {
+    return base
+  }
+
+  format!("{}<{}>", base, type_params.iter().map(format_type_tag).collect::<Vec<_>>().join(","))
+}
+
+/// Returns the struct tag of an object's Move type. Packages have no Move type.
+pub fn object_struct_tag(source: &pb::SuiObjectData) -> Option<pb::StructTag> {
+  use pb::{object_type::ObjectType, move_object_type::MoveObjectType};
+
+  let struct_tag = |address: &str, module: &str, name: &str, type_params: Vec<pb::TypeTag>| pb::StructTag {
+    address: normalize_address(address),
+    module: module.to_string(),
+    name: name.to_string(),
+    type_params: Some(pb::ListOfTypeTags {list: type_params}),
+  };
+
+  let object_type = match source.r#type.as_ref()?.object_type.as_ref()? {
+    ObjectType::Package(_) => return None,
+    ObjectType::Struct(object_type) => object_type.move_object_type.as_ref()?,
+  };
+
+  let struct_tag = match object_type {
+    MoveObjectType::Other(source) => source.clone(),
+    MoveObjectType::GasCoin(_) => {
+      let sui = pb::TypeTag {
+        type_tag: Some(pb::type_tag::TypeTag::Struct(struct_tag("0x2", "sui", "SUI", vec![]))),
+      };
+      struct_tag("0x2", "coin", "Coin", vec![sui])
+    },
+    MoveObjectType::StakedSui(_) => struct_tag("0x3", "staking_pool", "StakedSui", vec![]),
+    MoveObjectType::Coin(coin_type) => struct_tag("0x2", "coin", "Coin", vec![coin_type.clone()]),
+  };
+
+  Some(struct_tag)
+}
+
+/// A Move module i.e. `0x2::coin`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct ModulePattern {
+  pub package: String,
+  pub module: String,
+}
+
+impl FromStr for ModulePattern {
+  type Err = String;
+
+  fn from_str(source: &str) -> Result<Self, Self::Err> {
+    match source.split("::").collect::<Vec<_>>().as_slice() {
+      [package, module] if !module.is_empty() => Ok(Self {
+        package: parse_address(package)?,
+        module: module.to_string(),
+      }),
+      _ => Err(format!("'{}' is not a valid module, expected <package>::<module>", source)),
+    }
+  }
+}
+
+impl TryFrom<String> for ModulePattern {
+  type Error = String;
+
+  fn try_from(source: String) -> Result<Self, Self::Error> {
+    source.parse()
+  }
+}
+
+impl fmt::Display for ModulePattern {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "0x{}::{}", self.package, self.module)
+  }
+}
+
+impl From<ModulePattern> for String {
+  fn from(source: ModulePattern) -> Self {
+    source.to_string()
+  }
+}
+
+/// A Move function i.e. `0x2::coin::split`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct FunctionPattern {
+  pub package: String,
+  pub module: String,
+  pub function: String,
+}
+
+impl FromStr for FunctionPattern {
+  type Err = String;
+
+  fn from_str(source: &str) -> Result<Self, Self::Err> {
+    match source.split("::").collect::<Vec<_>>().as_slice() {
+      [package, module, function] if !module.is_empty() && !function.is_empty() => Ok(Self {
+        package: parse_address(package)?,
+        module: module.to_string(),
+        function: function.to_string(),
+      }),
+      _ => Err(format!("'{}' is not a valid function, expected <package>::<module>::<function>", source)),
+    }
+  }
+}
+
+impl TryFrom<String> for FunctionPattern {
+  type Error = String;
+
+  fn try_from(source: String) -> Result<Self, Self::Error> {
+    source.parse()
+  }
+}
+
+impl fmt::Display for FunctionPattern {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "0x{}::{}::{}", self.package, self.module, self.function)
+  }
+}
+
+impl From<FunctionPattern> for String {
+  fn from(source: FunctionPattern) -> Self {
+    source.to_string()
+  }
+}
+
+/// A Move struct type i.e. `0x2::coin::Coin`. If no type parameters are given then any instantiation of
+/// the struct matches, otherwise the type parameters must match as well i.e. `0x2::coin::Coin<0x2::sui::SUI>`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct TypePattern {
+  normalized: String,
+  has_type_params: bool,
+}
+
+impl TypePattern {
+  pub fn matches(&self, source: &pb::StructTag) -> bool {
+    let type_ = format_struct_tag(source);
+
+    if self.has_type_params {
+      type_ == self.normalized
+    } else {
+      type_.split('<').next() == Some(self.normalized.as_str())
+    }
+  }
+}
+
+impl FromStr for TypePattern {
+  type Err = String;
+
+  fn from_str(source: &str) -> Result<Self, Self::Err> {
+    let base = source.split('<').next().unwrap_or_default();
+
+    match base.trim().split("::").collect::<Vec<_>>().as_slice() {
+      [address, module, name] if !module.is_empty() && !name.is_empty() => {
+        parse_address(address)?;
+
+        let has_type_params = source.contains('<');
+        if has_type_params && !source.trim_end().ends_with('>') {
+          return Err(format!("'{}' is not a valid type, unbalanced type parameters", source))
+        }
+
+        Ok(Self {
+          normalized: normalize_type(source),
+          has_type_params,
+        })
+      },
+      _ => Err(format!("'{}' is not a valid type, expected <address>::<module>::<name>", source)),
+    }
+  }
+}
+
+impl TryFrom<String> for TypePattern {
+  type Error = String;
+
+  fn try_from(source: String) -> Result<Self, Self::Error> {
+    source.parse()
+  }
+}
+
+impl fmt::Display for TypePattern {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{}", self.normalized)
+  }
+}
+
+impl From<TypePattern> for String {
+  fn from(source: TypePattern) -> Self {
+    source.to_string()
+  }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct FilterRules {
+  #[serde(deserialize_with = "deserialize_addresses")]
+  pub packages: Vec<String>,
+  pub modules: Vec<ModulePattern>,
+  pub functions: Vec<FunctionPattern>,
+  pub event_types: Vec<TypePattern>,
+  pub object_types: Vec<TypePattern>,
+}
+
+impl FilterRules {
+  pub fn is_empty(&self) -> bool {
+    self.packages.is_empty()
+    && self.modules.is_empty()
+    && self.functions.is_empty()
+    && self.event_types.is_empty()
+    && self.object_types.is_empty()
+  }
+
+  fn matches_module(&self, package: &str, module: &str) -> bool {
+    let package = normalize_address(package);
+
+    self.packages.contains(&package)
+    || self.modules.iter().any(|m| m.package == package && m.module == module)
+  }
+
+  fn matches_move_call(&self, move_call: &pb::SuiProgrammableMoveCall) -> bool {
+    let package = move_call.package.as_ref().map(|p| normalize_address(&p.account_address)).unwrap_or_default();
+
+    self.matches_module(&package, &move_call.module)
+    || self.functions.iter().any(|f| {
+      f.package == package && f.module == move_call.module && f.function == move_call.function
+    })
+  }
+
+  fn matches_event(&self, event: &pb::SuiEvent) -> bool {
+    let package = event.package_id.as_ref().map(|p| p.account_address.as_str()).unwrap_or_default();
+
+    self.matches_module(package, &event.transaction_module)
+    || event.r#type.as_ref().is_some_and(|t| self.event_types.iter().any(|p| p.matches(t)))
+  }
+
+  /// A transaction matches if any of its Move calls or events matches any of the rules. Object type rules
+  /// apply to changed objects only.
+  pub fn matches_transaction(&self, source: &pb::CheckpointTransactionBlockResponse) -> bool {
+    let matches_move_calls = move_calls(source).any(|move_call| self.matches_move_call(move_call));
+    let matches_events = source.events.as_ref().is_some_and(|events| {
+      events.data.iter().any(|event| self.matches_event(event))
+    });
+
+    matches_move_calls || matches_events
+  }
+
+  /// An object matches if its type matches any of the type rules, or if the object is a matching package, or
+  /// if its type is defined in a matching package or module.
+  pub fn matches_object(&self, source: &pb::ChangedObject) -> bool {
+    let data = match source.data.as_ref() {
+      Some(data) => data,
+      None => return false,
+    };
+
+    let object_id = data.object_id.as_ref().map(|o| normalize_address(&o.account_address)).unwrap_or_default();
+    if self.packages.contains(&object_id) {
+      return true
+    }
+
+    object_struct_tag(data).is_some_and(|struct_tag| {
+      self.matches_module(&struct_tag.address, &struct_tag.module)
+      || self.object_types.iter().any(|p| p.matches(&struct_tag))
+    })
+  }
+}
+
+/// Iterates over all the Move calls of a programmable transaction
+pub fn move_calls(
+  source: &pb::CheckpointTransactionBlockResponse,
+) -> impl Iterator<Item = &pb::SuiProgrammableMoveCall> {
+  use pb::{
+    sui_transaction_block_data::SuiTransactionBlockData, sui_transaction_block_kind::SuiTransactionBlockKind,
+    sui_command::SuiCommand,
+  };
+
+  let ptb = source.transaction.as_ref()
+  .and_then(|t| t.data.as_ref())
+  .and_then(|d| d.sui_transaction_block_data.as_ref())
+  .and_then(|SuiTransactionBlockData::V1(d)| d.transaction.as_ref())
+  .and_then(|k| match k.sui_transaction_block_kind.as_ref()? {
+    SuiTransactionBlockKind::ProgrammableTransaction(ptb) => Some(ptb),
+    _ => None,
+  });
+
+  ptb
+  .into_iter()
+  .flat_map(|ptb| ptb.commands.iter())
+  .filter_map(|command| match command.sui_command.as_ref()? {
+    SuiCommand::MoveCall(move_call) => Some(move_call),
+    _ => None,
+  })
+}
@@ -0,0 +1,167 @@
+use std::{
+  collections::BTreeMap, sync::{Mutex, atomic::{AtomicBool, Ordering}},
+  time::{Duration, Instant},
+};
+use log::{debug, warn};
+use once_cell::sync::Lazy;
+use sui_json_rpc::api::ReadApiClient;
+use tokio::time::sleep;
+use crate::{metrics::METRICS, runtime::FirehoseStreamer};
+
+const TIP_POLL_INTERVAL: Duration = Duration::from_secs(10);
+
+/// The process wide status, exposed on `/healthz` and `/readyz`
+pub static HEALTH: Lazy<Health> = Lazy::new(Health::new);
+
+struct Progress {
+  /// The next checkpoint to stream, and when it last changed
+  checkpoint: u64,
+  checkpoint_at: Instant,
+  /// The latest checkpoint known to the RPC node, and when it last changed
+  tip: Option<u64>,
+  tip_at: Option<Instant>,
+}
+
+pub struct Health {
+  chain_id_checked: AtomicBool,
+  node_ready: AtomicBool,
+  progress: Mutex<Progress>,
+  /// The RPC calls currently in flight per method and the last error, for the stall diagnostics
+  in_flight: Mutex<BTreeMap<&'static str, u64>>,
+  last_error: Mutex<Option<String>>,
+}
+
+impl Health {
+  fn new() -> Self {
+    Self {
+      chain_id_checked: AtomicBool::new(false),
+      // Only a local sui-node can be not ready, in which case the supervisor keeps this up to date
+      node_ready: AtomicBool::new(true),
+      progress: Mutex::new(Progress {
+        checkpoint: 0,
+        checkpoint_at: Instant::now(),
+        tip: None,
+        tip_at: None,
+      }),
+      in_flight: Mutex::new(BTreeMap::new()),
+      last_error: Mutex::new(None),
+    }
+  }
+
+  pub fn set_chain_id_checked(&self) {
+    self.chain_id_checked.store(true, Ordering::Relaxed);
+  }
+
+  pub fn set_node_ready(&self, ready: bool) {
+    self.node_ready.store(ready, Ordering::Relaxed);
+  }
+
+  pub fn record_checkpoint(&self, seq: u64) {
+    let mut progress = self.progress.lock().unwrap();
+
+    if progress.checkpoint != seq {
+      progress.checkpoint = seq;
+      progress.checkpoint_at = Instant::now();
+    }
+  }
+
+  pub fn record_network_tip(&self, seq: u64) {
+    let mut progress = self.progress.lock().unwrap();
+
+    if progress.tip != Some(seq) {
+      progress.tip = Some(seq);
+      progress.tip_at = Some(Instant::now());
+    }
+  }
+
+  pub fn start_rpc(&self, method: &'static str) {
+    *self.in_flight.lock().unwrap().entry(method).or_insert(0) += 1;
+  }
+
+  pub fn finish_rpc(&self, method: &'static str) {
+    if let Some(count) = self.in_flight.lock().unwrap().get_mut(method) {
+      *count = count.saturating_sub(1);
+    }
+  }
+
+  pub fn record_error(&self, error: String) {
+    *self.last_error.lock().unwrap() = Some(error);
+  }
+
+  /// A one line summary of what the streamer is waiting for
+  pub fn diagnostics(&self) -> String {
+    let in_flight = self.in_flight
+    .lock()
+    .unwrap()
+    .iter()
+    .filter(|(_, count)| **count > 0)
+    .map(|(method, count)| format!("{}: {}", method, count))
+    .collect::<Vec<_>>();
+    let progress = self.progress.lock().unwrap();
+
+    format!(
+      "current checkpoint {} since {:?}, network tip {}, in-flight RPC calls [{}], last error: {}",
+      progress.checkpoint,
+      progress.checkpoint_at.elapsed(),
+      progress.tip.map(|tip| tip.to_string()).unwrap_or_else(|| "unknown".to_string()),
+      in_flight.join(", "),
+      self.last_error.lock().unwrap().as_deref().unwrap_or("none"),
+    )
+  }
+
+  /// Ready once the RPC node is reachable and serves the expected chain
+  pub fn check_ready(&self) -> Result<(), String> {
+    if !self.node_ready.load(Ordering::Relaxed) {
+      return Err("the sui-node is not ready".to_string())
+    }
+    if !self.chain_id_checked.load(Ordering::Relaxed) {
+      return Err("the chain id has not been checked yet".to_string())
+    }
+
+    Ok(())
+  }
+
+  /// Unhealthy when no checkpoint has been emitted for `stall_timeout` while the network tip kept moving
+  pub fn check_healthy(&self, stall_timeout: Duration) -> Result<(), String> {
+    let progress = self.progress.lock().unwrap();
+    let stalled_for = progress.checkpoint_at.elapsed();
+    let tip_moved = progress.tip_at.map(|tip_at| tip_at > progress.checkpoint_at).unwrap_or(false);
+    let behind_tip = progress.tip.map(|tip| tip >= progress.checkpoint).unwrap_or(false);
+
+    if stalled_for > stall_timeout && tip_moved && behind_tip {
+      return Err(format!(
+        "no checkpoint emitted for {:?}, stuck at checkpoint {} while the network is at {}",
+        stalled_for,
+        progress.checkpoint,
+        progress.tip.unwrap_or_default(),
+      ))
+    }
+
+    Ok(())
+  }
+}
+
+/// Keeps track of the network tip independently of the streamer, so that a stalled streamer can be told apart
+/// from a stalled network.
+pub async fn poll_network_tip(rpc_client_url: String) {
+  let http_client = match FirehoseStreamer::get_http_client(&rpc_client_url) {
+    Ok(http_client) => http_client,
+    Err(e) => {
+      warn!("Not polling the network tip: {}", e);
+      return
+    }
+  };
+
+  loop {
+    match http_client.get_latest_checkpoint_sequence_number().await {
+      Ok(seq) => {
+        let seq = seq.into_inner();
+        METRICS.set_network_tip_checkpoint(seq);
+        HEALTH.record_network_tip(seq);
+      }
+      Err(e) => debug!("Failed to poll the network tip: {:?}", e),
+    }
+
+    sleep(TIP_POLL_INTERVAL).await;
+  }
+}
@@ -0,0 +1,298 @@
+use std::{fs::File, io::{self, BufRead, BufReader}};
+use eyre::{Result, Report};
+use log::error;
+use prost::Message;
+use serde::Serialize;
+use serde_json::{Value, json};
+use crate::pb::sui::checkpoint as pb;
+
+/// Checks that blocks are well framed: every payload line belongs to a block, every block that starts ends, and
+/// block numbers increase. A new `INIT` means the reader restarted, so numbering may start over.
+#[derive(Default)]
+struct Framing {
+  initialized: bool,
+  open_block: Option<u64>,
+  last_block: Option<u64>,
+}
+
+impl Framing {
+  fn init(&mut self) -> Result<(), String> {
+    if let Some(open_block) = self.open_block.take() {
+      return Err(format!("INIT while block {} is not ended", open_block))
+    }
+
+    self.initialized = true;
+    self.last_block = None;
+
+    Ok(())
+  }
+
+  fn block_start(&mut self, seq: u64) -> Result<(), String> {
+    if !self.initialized {
+      return Err(format!("BLOCK_START {} before INIT", seq))
+    }
+    if let Some(open_block) = self.open_block {
+      return Err(format!("BLOCK_START {} while block {} is not ended", seq, open_block))
+    }
+    if let Some(last_block) = self.last_block.filter(|last_block| seq <= *last_block) {
+      return Err(format!("BLOCK_START {} does not follow block {}", seq, last_block))
+    }
+
+    self.open_block = Some(seq);
+
+    Ok(())
+  }
+
+  fn block_end(&mut self, seq: u64) -> Result<(), String> {
+    match self.open_block.take() {
+      Some(open_block) if open_block == seq => {
+        self.last_block = Some(seq);
+        Ok(())
+      }
+      Some(open_block) => Err(format!("BLOCK_END {} while block {} is open", seq, open_block)),
+      None => Err(format!("BLOCK_END {} without BLOCK_START", seq)),
+    }
+  }
+
+  /// The block a payload line belongs to
+  fn payload(&self, kind: &str) -> Result<u64, String> {
+    self.open_block.ok_or_else(|| format!("{} outside of a block", kind))
+  }
+
+  /// Checks that the input does not end in the middle of a block
+  fn finish(&self) -> Result<(), String> {
+    match self.open_block {
+      Some(open_block) => Err(format!("end of input while block {} is not ended", open_block)),
+      None => Ok(()),
+    }
+  }
+}
+
+fn parse_seq(kind: &str, value: Option<&str>) -> Result<u64, String> {
+  value
+  .ok_or_else(|| format!("{} without block number", kind))?
+  .parse::<u64>()
+  .map_err(|e| format!("{} with invalid block number: {}", kind, e))
+}
+
+fn decode<M: Message + Default + Serialize>(kind: &str, payload: Option<&str>) -> Result<(M, Value), String> {
+  let payload = payload.ok_or_else(|| format!("{} without payload", kind))?;
+  let bytes = base64::decode(payload).map_err(|e| format!("{} payload is not valid base64: {}", kind, e))?;
+  let message = M::decode(bytes.as_slice()).map_err(|e| format!("{} payload cannot be decoded: {}", kind, e))?;
+  let value = serde_json::to_value(&message).map_err(|e| format!("{} payload cannot be rendered: {}", kind, e))?;
+
+  Ok((message, value))
+}
+
+/// Decodes a single FIRE line, or returns `None` for any other line i.e. the blank lines between FIRE lines and
+/// the logs of the reader node
+fn inspect_line(framing: &mut Framing, line: &str) -> Option<Result<Value, String>> {
+  let mut parts = line.trim().strip_prefix("FIRE ")?.split(' ');
+  let kind = parts.next().unwrap_or_default();
+
+  Some(decode_line(framing, kind, parts))
+}
+
+fn decode_line<'a>(
+  framing: &mut Framing,
+  kind: &str,
+  mut parts: impl Iterator<Item = &'a str>,
+) -> Result<Value, String> {
+  match kind {
+    // Format is FIRE INIT sui-node <PACKAGE_VERSION> <MAJOR_VERSION> <MINOR_VERSION> <CHAIN_ID>
+    "INIT" => {
+      let fields = parts.collect::<Vec<_>>();
+      if fields.len() != 6 {
+        return Err(format!("INIT with {} fields instead of 6", fields.len()))
+      }
+
+      framing.init()?;
+
+      Ok(json!({
+        "kind": kind,
+        "node": fields[0],
+        "version": fields[1],
+        "chain": fields[2],
+        "major_version": fields[3],
+        "minor_version": fields[4],
+        "chain_id": fields[5],
+      }))
+    }
+    "BLOCK_START" | "BLOCK_END" => {
+      let seq = parse_seq(kind, parts.next())?;
+
+      if kind == "BLOCK_START" {
+        framing.block_start(seq)?;
+      } else {
+        framing.block_end(seq)?;
+      }
+
+      Ok(json!({"kind": kind, "block": seq}))
+    }
+    "CHECKPOINT" => {
+      let block = framing.payload(kind)?;
+      let (checkpoint, value) = decode::<pb::Checkpoint>(kind, parts.next())?;
+
+      if checkpoint.sequence_number != block {
+        return Err(format!("CHECKPOINT {} in block {}", checkpoint.sequence_number, block))
+      }
+
+      Ok(json!({"kind": kind, "block": block, "payload": value}))
+    }
+    "CHECKPOINT_STATS" => payload_json::<pb::CheckpointStats>(framing, kind, parts.next()),
+    "TRX" => payload_json::<pb::CheckpointTransactionBlockResponse>(framing, kind, parts.next()),
+    "OBJ" => payload_json::<pb::ChangedObject>(framing, kind, parts.next()),
+    "EPOCH" => payload_json::<pb::EpochChange>(framing, kind, parts.next()),
+    "SYSTEM_STATE" => payload_json::<pb::SuiSystemStateSummary>(framing, kind, parts.next()),
+    _ => Err(format!("unknown line kind {}", kind)),
+  }
+}
+
+fn payload_json<M: Message + Default + Serialize>(
+  framing: &Framing,
+  kind: &str,
+  payload: Option<&str>,
+) -> Result<Value, String> {
+  let block = framing.payload(kind)?;
+  let (_, value) = decode::<M>(kind, payload)?;
+
+  Ok(json!({"kind": kind, "block": block, "payload": value}))
+}
+
+/// Reads FIRE lines from `path`, or stdin if none is given, and prints every decoded line as JSON to stdout.
+/// Framing and decoding errors are logged with their line number, and fail the command once the whole input has
+/// been read.
+pub fn inspect(path: Option<&str>) -> Result<()> {
+  let reader: Box<dyn BufRead> = match path {
+    Some(path) => Box::new(BufReader::new(File::open(path).map_err(|e| {
+      Report::msg(format!("Failed to open {}: {}", path, e))
+    })?)),
+    None => Box::new(BufReader::new(io::stdin())),
+  };
+
+  let mut framing = Framing::default();
+  let mut errors = 0;
+
+  for (index, line) in reader.lines().enumerate() {
+    let line_number = index + 1;
+
+    match inspect_line(&mut framing, &line?) {
+      Some(Ok(mut value)) => {
+        value["line"] = json!(line_number);
+        println!("{}", serde_json::to_string_pretty(&value)?);
+      }
+      Some(Err(e)) => {
+        error!("line {}: {}", line_number, e);
+        errors += 1;
+      }
+      None => {}
+    }
+  }
+
+  if let Err(e) = framing.finish() {
+    error!("{}", e);
+    errors += 1;
+  }
+
+  if errors > 0 {
+    return Err(Report::msg(format!("Found {} invalid FIRE lines", errors)))
+  }
+
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const INIT: &str = "FIRE INIT sui-node 0.1.0 sui 0 0 4btiuiMPvEENsttpZC7CZ53DruC3MAgfznDbASZ7DR6S";
+
+  fn stats_line() -> String {
+    let stats = pb::CheckpointStats {transactions: 1, ..Default::default()};
+    format!("FIRE CHECKPOINT_STATS {}", base64::encode(stats.encode_to_vec()))
+  }
+
+  /// Inspects `lines` and returns the errors, including the one for an unterminated input
+  fn errors(lines: &[&str]) -> Vec<String> {
+    let mut framing = Framing::default();
+
+    lines
+    .iter()
+    .filter_map(|line| inspect_line(&mut framing, line))
+    .filter_map(Result::err)
+    .chain(framing.finish().err())
+    .collect()
+  }
+
+  #[test]
+  fn accepts_well_framed_blocks() {
+    let stats = stats_line();
+    let lines = [
+      INIT, "", "FIRE BLOCK_START 1", stats.as_str(), "FIRE BLOCK_END 1", "FIRE BLOCK_START 3", "FIRE BLOCK_END 3",
+    ];
+
+    assert_eq!(errors(&lines), Vec::<String>::new());
+  }
+
+  #[test]
+  fn rejects_nested_block_start() {
+    assert_eq!(
+      errors(&[INIT, "FIRE BLOCK_START 1", "FIRE BLOCK_START 2", "FIRE BLOCK_END 1"]),
+      vec!["BLOCK_START 2 while block 1 is not ended"],
+    );
+  }
+
+  #[test]
+  fn rejects_mismatched_block_end() {
+    assert_eq!(
+      errors(&[INIT, "FIRE BLOCK_START 1", "FIRE BLOCK_END 2"]),
+      vec!["BLOCK_END 2 while block 1 is open"],
+    );
+    assert_eq!(errors(&[INIT, "FIRE BLOCK_END 1"]), vec!["BLOCK_END 1 without BLOCK_START"]);
+  }
+
+  #[test]
+  fn rejects_payload_outside_of_a_block() {
+    let stats = stats_line();
+
+    assert_eq!(
+      errors(&[INIT, "FIRE BLOCK_START 1", "FIRE BLOCK_END 1", stats.as_str()]),
+      vec!["CHECKPOINT_STATS outside of a block"],
+    );
+  }
+
+  #[test]
+  fn rejects_non_increasing_blocks() {
+    assert_eq!(
+      errors(&[INIT, "FIRE BLOCK_START 2", "FIRE BLOCK_END 2", "FIRE BLOCK_START 2", "FIRE BLOCK_END 2"]),
+      vec!["BLOCK_START 2 does not follow block 2", "BLOCK_END 2 without BLOCK_START"],
+    );
+    assert_eq!(
+      errors(&[INIT, "FIRE BLOCK_START 2", "FIRE BLOCK_END 2", "FIRE BLOCK_START 1", "FIRE BLOCK_END 1"]),
+      vec!["BLOCK_START 1 does not follow block 2", "BLOCK_END 1 without BLOCK_START"],
+    );
+  }
+
+  #[test]
+  fn restarts_numbering_after_init() {
+    assert_eq!(
+      errors(&[INIT, "FIRE BLOCK_START 2", "FIRE BLOCK_END 2", INIT, "FIRE BLOCK_START 1", "FIRE BLOCK_END 1"]),
+      Vec::<String>::new(),
+    );
+    assert_eq!(
+      errors(&[INIT, "FIRE BLOCK_START 2", INIT, "FIRE BLOCK_START 1", "FIRE BLOCK_END 1"]),
+      vec!["INIT while block 2 is not ended"],
+    );
+    assert_eq!(errors(&["FIRE BLOCK_START 1"]), vec!["BLOCK_START 1 before INIT"]);
+  }
+
+  #[test]
+  fn rejects_unterminated_input() {
+    let stats = stats_line();
+
+    assert_eq!(
+      errors(&[INIT, "FIRE BLOCK_START 1", stats.as_str()]),
+      vec!["end of input while block 1 is not ended"],
+    );
+  }
+}
@@ -1,6 +1,19 @@
 pub mod runtime;
 pub mod sui_node;
 pub mod checkpoint_handler;
+pub mod checkpoint_stats;
+pub mod config;
 pub mod convert;
+pub mod fetch;
+pub mod filter;
+pub mod health;
+pub mod inspect;
+pub mod metrics;
 pub mod pb;
+pub mod position;
 pub mod process_manager;
+pub mod processor;
+pub mod projection;
+pub mod server;
+pub mod stream;
+pub mod telemetry;
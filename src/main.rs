@@ -1,4 +1,4 @@
-use env_logger::Env;
+use std::process;
 use eyre::Result;
 use sui_sf_indexer::{
   process_manager::ProcessManager,
@@ -6,10 +6,9 @@ use sui_sf_indexer::{
 
 #[tokio::main]
 async fn main() -> Result<()> {
-  env_logger::Builder::from_env(Env::default().default_filter_or("info")).init();
+  let mut pm = ProcessManager::from_args()?;
+  pm.handle_signals();
+  let exit = pm.start().await?;
 
-  let mut pm = ProcessManager::new();
-  pm.start().await;
-
-  Ok(())
+  process::exit(exit.code());
 }
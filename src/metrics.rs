@@ -0,0 +1,143 @@
+use std::{fmt, future::Future, time::Duration};
+use once_cell::sync::Lazy;
+use prometheus::{
+  Encoder, HistogramVec, IntCounter, IntCounterVec, IntGauge, Registry, TextEncoder, histogram_opts, opts,
+};
+use crate::health::HEALTH;
+
+/// The process wide metrics, exposed in the Prometheus format on `/metrics`
+pub static METRICS: Lazy<Metrics> = Lazy::new(Metrics::new);
+
+pub struct Metrics {
+  registry: Registry,
+  /// The sequence number of the next checkpoint to stream
+  pub current_checkpoint: IntGauge,
+  /// The latest checkpoint known to the RPC node
+  pub network_tip_checkpoint: IntGauge,
+  pub checkpoint_lag: IntGauge,
+  pub checkpoints_emitted: IntCounter,
+  pub transactions_emitted: IntCounter,
+  pub objects_emitted: IntCounter,
+  /// Bytes of encoded protobuf payload per FIRE line kind
+  pub emitted_bytes: IntCounterVec,
+  pub rpc_duration: HistogramVec,
+  pub rpc_errors: IntCounterVec,
+  /// Retries per retried operation, which may span several RPC calls
+  pub retries: IntCounterVec,
+}
+
+impl Metrics {
+  fn new() -> Self {
+    let registry = Registry::new_custom(Some("sui_sf".to_string()), None).expect("valid metrics prefix");
+
+    let metrics = Self {
+      current_checkpoint: IntGauge::new("current_checkpoint", "The next checkpoint to stream").unwrap(),
+      network_tip_checkpoint: IntGauge::new(
+        "network_tip_checkpoint",
+        "The latest checkpoint known to the RPC node",
+      ).unwrap(),
+      checkpoint_lag: IntGauge::new(
+        "checkpoint_lag",
+        "The number of checkpoints between the network tip and the current checkpoint",
+      ).unwrap(),
+      checkpoints_emitted: IntCounter::new("checkpoints_emitted_total", "Checkpoints emitted").unwrap(),
+      transactions_emitted: IntCounter::new("transactions_emitted_total", "Transactions emitted").unwrap(),
+      objects_emitted: IntCounter::new("objects_emitted_total", "Changed objects emitted").unwrap(),
+      emitted_bytes: IntCounterVec::new(
+        opts!("emitted_bytes_total", "Bytes of protobuf payload emitted"),
+        &["kind"],
+      ).unwrap(),
+      rpc_duration: HistogramVec::new(
+        histogram_opts!(
+          "rpc_duration_seconds",
+          "RPC call latency",
+          vec![0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0]
+        ),
+        &["method"],
+      ).unwrap(),
+      rpc_errors: IntCounterVec::new(opts!("rpc_errors_total", "Failed RPC calls"), &["method"]).unwrap(),
+      retries: IntCounterVec::new(opts!("retries_total", "Retries of failed operations"), &["operation"]).unwrap(),
+      registry,
+    };
+
+    metrics.registry.register(Box::new(metrics.current_checkpoint.clone())).unwrap();
+    metrics.registry.register(Box::new(metrics.network_tip_checkpoint.clone())).unwrap();
+    metrics.registry.register(Box::new(metrics.checkpoint_lag.clone())).unwrap();
+    metrics.registry.register(Box::new(metrics.checkpoints_emitted.clone())).unwrap();
+    metrics.registry.register(Box::new(metrics.transactions_emitted.clone())).unwrap();
+    metrics.registry.register(Box::new(metrics.objects_emitted.clone())).unwrap();
+    metrics.registry.register(Box::new(metrics.emitted_bytes.clone())).unwrap();
+    metrics.registry.register(Box::new(metrics.rpc_duration.clone())).unwrap();
+    metrics.registry.register(Box::new(metrics.rpc_errors.clone())).unwrap();
+    metrics.registry.register(Box::new(metrics.retries.clone())).unwrap();
+
+    metrics
+  }
+
+  /// Renders all the metrics in the Prometheus text format
+  pub fn encode(&self) -> Vec<u8> {
+    let mut buf = vec![];
+    TextEncoder::new().encode(&self.registry.gather(), &mut buf).expect("encode metrics");
+
+    buf
+  }
+
+  pub fn set_current_checkpoint(&self, seq: u64) {
+    self.current_checkpoint.set(seq as i64);
+    self.update_lag();
+  }
+
+  pub fn set_network_tip_checkpoint(&self, seq: u64) {
+    self.network_tip_checkpoint.set(seq as i64);
+    self.update_lag();
+  }
+
+  fn update_lag(&self) {
+    let lag = self.network_tip_checkpoint.get() - self.current_checkpoint.get();
+    self.checkpoint_lag.set(lag.max(0));
+  }
+
+  /// Use as the notify callback of `backoff::future::retry_notify`
+  pub fn retry_notifier<E: fmt::Display>(&self, operation: &'static str) -> impl FnMut(E, Duration) + '_ {
+    move |error, _| {
+      self.retries.with_label_values(&[operation]).inc();
+      HEALTH.record_error(format!("{}: {}", operation, error));
+    }
+  }
+}
+
+/// Counts an RPC call as in flight until it is dropped, so that cancelled calls are accounted for as well
+struct InFlight(&'static str);
+
+impl InFlight {
+  fn start(method: &'static str) -> Self {
+    HEALTH.start_rpc(method);
+    Self(method)
+  }
+}
+
+impl Drop for InFlight {
+  fn drop(&mut self) {
+    HEALTH.finish_rpc(self.0);
+  }
+}
+
+/// Records the latency and the outcome of an RPC call
+pub async fn observe_rpc<T, E, F>(method: &'static str, call: F) -> Result<T, E>
+where
+  E: fmt::Debug,
+  F: Future<Output = Result<T, E>>,
+{
+  let in_flight = InFlight::start(method);
+  let timer = METRICS.rpc_duration.with_label_values(&[method]).start_timer();
+  let result = call.await;
+  timer.observe_duration();
+  drop(in_flight);
+
+  if let Err(e) = &result {
+    METRICS.rpc_errors.with_label_values(&[method]).inc();
+    HEALTH.record_error(format!("{}: {:?}", method, e));
+  }
+
+  result
+}
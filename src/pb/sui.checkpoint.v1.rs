@@ -1,3 +1,4 @@
+#[derive(serde::Serialize)]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct CheckpointData {
@@ -8,6 +9,7 @@ pub struct CheckpointData {
     #[prost(message, repeated, tag = "3")]
     pub changed_objects: ::prost::alloc::vec::Vec<ChangedObject>,
 }
+#[derive(serde::Serialize)]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct Checkpoint {
@@ -47,6 +49,47 @@ pub struct Checkpoint {
     #[prost(bytes = "vec", tag = "11")]
     pub validator_signature: ::prost::alloc::vec::Vec<u8>,
 }
+/// Aggregates computed over all the transactions of a checkpoint
+#[derive(serde::Serialize)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CheckpointStats {
+    /// Checkpoint sequence number
+    #[prost(uint64, tag = "1")]
+    pub sequence_number: u64,
+    /// Total number of transactions in the checkpoint
+    #[prost(uint64, tag = "2")]
+    pub transactions: u64,
+    /// Number of transactions that executed successfully
+    #[prost(uint64, tag = "3")]
+    pub successful_transactions: u64,
+    /// Number of transactions that failed
+    #[prost(uint64, tag = "4")]
+    pub failed_transactions: u64,
+    /// Number of events emitted by all transactions
+    #[prost(uint64, tag = "5")]
+    pub events: u64,
+    /// Number of objects created, mutated and deleted by all transactions
+    #[prost(uint64, tag = "6")]
+    pub created_objects: u64,
+    #[prost(uint64, tag = "7")]
+    pub mutated_objects: u64,
+    #[prost(uint64, tag = "8")]
+    pub deleted_objects: u64,
+    /// The sum of the gas used by all transactions
+    #[prost(message, optional, tag = "9")]
+    pub gas_used: ::core::option::Option<GasCostSummary>,
+    /// Number of distinct transaction senders
+    #[prost(uint64, tag = "10")]
+    pub distinct_senders: u64,
+    /// Number of Move calls per package. The key is the package id
+    #[prost(map = "string, uint64", tag = "11")]
+    pub move_calls_per_package: ::std::collections::HashMap<
+        ::prost::alloc::string::String,
+        u64,
+    >,
+}
+#[derive(serde::Serialize)]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct CheckpointTransactionBlockResponse {
@@ -67,7 +110,22 @@ pub struct CheckpointTransactionBlockResponse {
     pub confirmed_local_execution: ::core::option::Option<bool>,
     #[prost(uint64, tag = "8")]
     pub checkpoint: u64,
+    /// The watched addresses this transaction involves. Empty unless the streamer runs with watched addresses
+    #[prost(message, repeated, tag = "9")]
+    pub address_matches: ::prost::alloc::vec::Vec<AddressMatch>,
+}
+#[derive(serde::Serialize)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct AddressMatch {
+    /// Hex encoded watched address
+    #[prost(string, tag = "1")]
+    pub address: ::prost::alloc::string::String,
+    /// All the ways the transaction involves the address
+    #[prost(enumeration = "AddressMatchReason", repeated, tag = "2")]
+    pub reasons: ::prost::alloc::vec::Vec<i32>,
 }
+#[derive(serde::Serialize)]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct GasCostSummary {
@@ -84,6 +142,7 @@ pub struct GasCostSummary {
     #[prost(uint64, tag = "4")]
     pub non_refundable_storage_fee: u64,
 }
+#[derive(serde::Serialize)]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct EndOfEpochData {
@@ -103,6 +162,126 @@ pub struct EndOfEpochData {
     #[prost(message, repeated, tag = "3")]
     pub epoch_commitments: ::prost::alloc::vec::Vec<CheckpointCommitment>,
 }
+/// Emitted along with the last checkpoint of every epoch
+#[derive(serde::Serialize)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct EpochChange {
+    /// The epoch that ended
+    #[prost(uint64, tag = "1")]
+    pub epoch: u64,
+    /// The first checkpoint of the epoch. Unset if the streamer did not observe the start of the epoch
+    #[prost(uint64, optional, tag = "2")]
+    pub start_checkpoint: ::core::option::Option<u64>,
+    /// The last checkpoint of the epoch
+    #[prost(uint64, tag = "3")]
+    pub end_checkpoint: u64,
+    /// Timestamp of the start of the epoch. Unset if the streamer did not observe the start of the epoch
+    #[prost(uint64, optional, tag = "4")]
+    pub start_timestamp_ms: ::core::option::Option<u64>,
+    /// Timestamp of the last checkpoint of the epoch
+    #[prost(uint64, tag = "5")]
+    pub end_timestamp_ms: u64,
+    /// The protocol version that is in effect during the next epoch
+    #[prost(uint64, tag = "6")]
+    pub next_epoch_protocol_version: u64,
+    /// The committee of the next epoch as validator pub key and stake pairs
+    #[prost(message, repeated, tag = "7")]
+    pub next_epoch_committee: ::prost::alloc::vec::Vec<NextEpochCommittee>,
+    /// The total gas costs of all transactions included in the epoch
+    #[prost(message, optional, tag = "8")]
+    pub epoch_gas_cost_summary: ::core::option::Option<GasCostSummary>,
+    /// The system transaction that started the next epoch
+    #[prost(message, optional, tag = "9")]
+    pub change_epoch: ::core::option::Option<SuiChangeEpoch>,
+}
+/// Snapshot of the Sui system state taken at the start of an epoch, as left by the ChangeEpoch transaction of the
+/// last checkpoint of the previous epoch.
+#[derive(serde::Serialize)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SuiSystemStateSummary {
+    /// The current epoch ID, starting from 0.
+    #[prost(uint64, tag = "1")]
+    pub epoch: u64,
+    /// The current protocol version, starting from 1.
+    #[prost(uint64, tag = "2")]
+    pub protocol_version: u64,
+    /// The current version of the system state data structure type.
+    #[prost(uint64, tag = "3")]
+    pub system_state_version: u64,
+    /// The storage rebates of all the objects on-chain stored in the storage fund.
+    #[prost(uint64, tag = "4")]
+    pub storage_fund_total_object_storage_rebates: u64,
+    /// The non-refundable portion of the storage fund coming from storage reinvestment, non-refundable
+    /// storage rebates and any leftover staking rewards.
+    #[prost(uint64, tag = "5")]
+    pub storage_fund_non_refundable_balance: u64,
+    /// The reference gas price for the current epoch.
+    #[prost(uint64, tag = "6")]
+    pub reference_gas_price: u64,
+    /// Whether the system is running in a downgraded safe mode due to a non-recoverable bug.
+    #[prost(bool, tag = "7")]
+    pub safe_mode: bool,
+    /// Unix timestamp of the current epoch start
+    #[prost(uint64, tag = "8")]
+    pub epoch_start_timestamp_ms: u64,
+    /// The duration of an epoch, in milliseconds.
+    #[prost(uint64, tag = "9")]
+    pub epoch_duration_ms: u64,
+    /// Balance of SUI set aside for stake subsidies that will be drawn down over time.
+    #[prost(uint64, tag = "10")]
+    pub stake_subsidy_balance: u64,
+    /// Total amount of stake from all active validators at the beginning of the epoch.
+    #[prost(uint64, tag = "11")]
+    pub total_stake: u64,
+    /// The list of active validators in the current epoch.
+    #[prost(message, repeated, tag = "12")]
+    pub active_validators: ::prost::alloc::vec::Vec<SuiValidatorSummary>,
+}
+#[derive(serde::Serialize)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SuiValidatorSummary {
+    /// Hex encoded validator address
+    #[prost(string, tag = "1")]
+    pub sui_address: ::prost::alloc::string::String,
+    /// Base64 encoded protocol public key
+    #[prost(string, tag = "2")]
+    pub protocol_pubkey: ::prost::alloc::string::String,
+    #[prost(string, tag = "3")]
+    pub name: ::prost::alloc::string::String,
+    #[prost(uint64, tag = "4")]
+    pub voting_power: u64,
+    #[prost(uint64, tag = "5")]
+    pub gas_price: u64,
+    #[prost(uint64, tag = "6")]
+    pub commission_rate: u64,
+    #[prost(uint64, tag = "7")]
+    pub next_epoch_stake: u64,
+    #[prost(uint64, tag = "8")]
+    pub next_epoch_gas_price: u64,
+    #[prost(uint64, tag = "9")]
+    pub next_epoch_commission_rate: u64,
+    #[prost(message, optional, tag = "10")]
+    pub staking_pool_id: ::core::option::Option<ObjectId>,
+    /// The total number of SUI tokens in the staking pool
+    #[prost(uint64, tag = "11")]
+    pub staking_pool_sui_balance: u64,
+    /// The epoch stake rewards will be added here at the end of each epoch.
+    #[prost(uint64, tag = "12")]
+    pub rewards_pool: u64,
+    /// Total number of pool tokens issued by the pool.
+    #[prost(uint64, tag = "13")]
+    pub pool_token_balance: u64,
+    /// Pending stake amount for this epoch.
+    #[prost(uint64, tag = "14")]
+    pub pending_stake: u64,
+    /// Pending stake withdrawn during the current epoch, emptied at epoch boundaries.
+    #[prost(uint64, tag = "15")]
+    pub pending_total_sui_withdraw: u64,
+}
+#[derive(serde::Serialize)]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct NextEpochCommittee {
@@ -111,6 +290,7 @@ pub struct NextEpochCommittee {
     #[prost(uint64, tag = "2")]
     pub stake_unit: u64,
 }
+#[derive(serde::Serialize)]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct CheckpointCommitment {
@@ -121,6 +301,7 @@ pub struct CheckpointCommitment {
 }
 /// Nested message and enum types in `CheckpointCommitment`.
 pub mod checkpoint_commitment {
+    #[derive(serde::Serialize)]
     #[allow(clippy::derive_partial_eq_without_eq)]
     #[derive(Clone, PartialEq, ::prost::Oneof)]
     pub enum CheckpointCommitment {
@@ -128,6 +309,7 @@ pub mod checkpoint_commitment {
         EcmhLiveObjectSetDigest(super::EcmhLiveObjectSetDigest),
     }
 }
+#[derive(serde::Serialize)]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct EcmhLiveObjectSetDigest {
@@ -135,12 +317,14 @@ pub struct EcmhLiveObjectSetDigest {
     #[prost(string, tag = "1")]
     pub digest: ::prost::alloc::string::String,
 }
+#[derive(serde::Serialize)]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct SuiTransactionBlock {
     #[prost(message, optional, tag = "1")]
     pub data: ::core::option::Option<SuiTransactionBlockData>,
 }
+#[derive(serde::Serialize)]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct SuiTransactionBlockData {
@@ -151,6 +335,7 @@ pub struct SuiTransactionBlockData {
 }
 /// Nested message and enum types in `SuiTransactionBlockData`.
 pub mod sui_transaction_block_data {
+    #[derive(serde::Serialize)]
     #[allow(clippy::derive_partial_eq_without_eq)]
     #[derive(Clone, PartialEq, ::prost::Oneof)]
     pub enum SuiTransactionBlockData {
@@ -158,6 +343,7 @@ pub mod sui_transaction_block_data {
         V1(super::SuiTransactionBlockDataV1),
     }
 }
+#[derive(serde::Serialize)]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct SuiTransactionBlockDataV1 {
@@ -168,6 +354,7 @@ pub struct SuiTransactionBlockDataV1 {
     #[prost(message, optional, tag = "3")]
     pub gas_data: ::core::option::Option<SuiGasData>,
 }
+#[derive(serde::Serialize)]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct SuiTransactionBlockKind {
@@ -181,6 +368,7 @@ pub struct SuiTransactionBlockKind {
 }
 /// Nested message and enum types in `SuiTransactionBlockKind`.
 pub mod sui_transaction_block_kind {
+    #[derive(serde::Serialize)]
     #[allow(clippy::derive_partial_eq_without_eq)]
     #[derive(Clone, PartialEq, ::prost::Oneof)]
     pub enum SuiTransactionBlockKind {
@@ -198,6 +386,7 @@ pub mod sui_transaction_block_kind {
         ProgrammableTransaction(super::SuiProgrammableTransactionBlock),
     }
 }
+#[derive(serde::Serialize)]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct SuiGasData {
@@ -210,6 +399,7 @@ pub struct SuiGasData {
     #[prost(uint64, tag = "4")]
     pub budget: u64,
 }
+#[derive(serde::Serialize)]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct SuiObjectRef {
@@ -223,6 +413,7 @@ pub struct SuiObjectRef {
     #[prost(string, tag = "3")]
     pub digest: ::prost::alloc::string::String,
 }
+#[derive(serde::Serialize)]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct SuiChangeEpoch {
@@ -237,18 +428,21 @@ pub struct SuiChangeEpoch {
     #[prost(uint64, tag = "5")]
     pub epoch_start_timestamp_ms: u64,
 }
+#[derive(serde::Serialize)]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct SuiGenesisTransaction {
     #[prost(message, repeated, tag = "1")]
     pub objects: ::prost::alloc::vec::Vec<ObjectId>,
 }
+#[derive(serde::Serialize)]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct ObjectId {
     #[prost(string, tag = "1")]
     pub account_address: ::prost::alloc::string::String,
 }
+#[derive(serde::Serialize)]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct SuiConsensusCommitPrologue {
@@ -259,6 +453,7 @@ pub struct SuiConsensusCommitPrologue {
     #[prost(uint64, tag = "3")]
     pub commit_timestamp_ms: u64,
 }
+#[derive(serde::Serialize)]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct SuiProgrammableTransactionBlock {
@@ -270,6 +465,7 @@ pub struct SuiProgrammableTransactionBlock {
     #[prost(message, repeated, tag = "2")]
     pub commands: ::prost::alloc::vec::Vec<SuiCommand>,
 }
+#[derive(serde::Serialize)]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct SuiCallArg {
@@ -278,6 +474,7 @@ pub struct SuiCallArg {
 }
 /// Nested message and enum types in `SuiCallArg`.
 pub mod sui_call_arg {
+    #[derive(serde::Serialize)]
     #[allow(clippy::derive_partial_eq_without_eq)]
     #[derive(Clone, PartialEq, ::prost::Oneof)]
     pub enum SuiCallArg {
@@ -289,6 +486,7 @@ pub mod sui_call_arg {
         Pure(super::SuiPureValue),
     }
 }
+#[derive(serde::Serialize)]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct SuiObjectArg {
@@ -297,6 +495,7 @@ pub struct SuiObjectArg {
 }
 /// Nested message and enum types in `SuiObjectArg`.
 pub mod sui_object_arg {
+    #[derive(serde::Serialize)]
     #[allow(clippy::derive_partial_eq_without_eq)]
     #[derive(Clone, PartialEq, ::prost::Oneof)]
     pub enum SuiObjectArg {
@@ -309,6 +508,7 @@ pub mod sui_object_arg {
         SharedObject(super::SharedObject),
     }
 }
+#[derive(serde::Serialize)]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct ImmOrOwnedObject {
@@ -319,6 +519,7 @@ pub struct ImmOrOwnedObject {
     #[prost(string, tag = "3")]
     pub digest: ::prost::alloc::string::String,
 }
+#[derive(serde::Serialize)]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct SharedObject {
@@ -329,6 +530,7 @@ pub struct SharedObject {
     #[prost(bool, tag = "3")]
     pub mutable: bool,
 }
+#[derive(serde::Serialize)]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct SuiPureValue {
@@ -337,6 +539,7 @@ pub struct SuiPureValue {
     #[prost(message, optional, tag = "2")]
     pub value: ::core::option::Option<SuiJsonValue>,
 }
+#[derive(serde::Serialize)]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct TypeTag {
@@ -345,6 +548,7 @@ pub struct TypeTag {
 }
 /// Nested message and enum types in `TypeTag`.
 pub mod type_tag {
+    #[derive(serde::Serialize)]
     #[allow(clippy::derive_partial_eq_without_eq)]
     #[derive(Clone, PartialEq, ::prost::Oneof)]
     pub enum TypeTag {
@@ -372,12 +576,14 @@ pub mod type_tag {
         U256(()),
     }
 }
+#[derive(serde::Serialize)]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct ListOfTypeTags {
     #[prost(message, repeated, tag = "1")]
     pub list: ::prost::alloc::vec::Vec<TypeTag>,
 }
+#[derive(serde::Serialize)]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct StructTag {
@@ -390,6 +596,7 @@ pub struct StructTag {
     #[prost(message, optional, tag = "4")]
     pub type_params: ::core::option::Option<ListOfTypeTags>,
 }
+#[derive(serde::Serialize)]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct SuiJsonValue {
@@ -398,6 +605,7 @@ pub struct SuiJsonValue {
 }
 /// Nested message and enum types in `SuiJsonValue`.
 pub mod sui_json_value {
+    #[derive(serde::Serialize)]
     #[allow(clippy::derive_partial_eq_without_eq)]
     #[derive(Clone, PartialEq, ::prost::Oneof)]
     pub enum Value {
@@ -415,18 +623,21 @@ pub mod sui_json_value {
         Object(super::SuiJsonValueMap),
     }
 }
+#[derive(serde::Serialize)]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct SuiJsonValueMap {
     #[prost(map = "string, message", tag = "6")]
     pub map: ::std::collections::HashMap<::prost::alloc::string::String, SuiJsonValue>,
 }
+#[derive(serde::Serialize)]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct ListOfJsonValues {
     #[prost(message, repeated, tag = "1")]
     pub list: ::prost::alloc::vec::Vec<SuiJsonValue>,
 }
+#[derive(serde::Serialize)]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct SuiCommand {
@@ -435,6 +646,7 @@ pub struct SuiCommand {
 }
 /// Nested message and enum types in `SuiCommand`.
 pub mod sui_command {
+    #[derive(serde::Serialize)]
     #[allow(clippy::derive_partial_eq_without_eq)]
     #[derive(Clone, PartialEq, ::prost::Oneof)]
     pub enum SuiCommand {
@@ -469,6 +681,7 @@ pub mod sui_command {
         MakeMoveVec(super::MakeMoveVecPair),
     }
 }
+#[derive(serde::Serialize)]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct TransferObjectsPair {
@@ -477,6 +690,7 @@ pub struct TransferObjectsPair {
     #[prost(message, optional, tag = "2")]
     pub two: ::core::option::Option<SuiArgument>,
 }
+#[derive(serde::Serialize)]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct SplitCoinsPair {
@@ -485,6 +699,7 @@ pub struct SplitCoinsPair {
     #[prost(message, repeated, tag = "2")]
     pub two: ::prost::alloc::vec::Vec<SuiArgument>,
 }
+#[derive(serde::Serialize)]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct MergeCoinsPair {
@@ -493,12 +708,14 @@ pub struct MergeCoinsPair {
     #[prost(message, repeated, tag = "2")]
     pub two: ::prost::alloc::vec::Vec<SuiArgument>,
 }
+#[derive(serde::Serialize)]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct ListOfObjects {
     #[prost(message, repeated, tag = "1")]
     pub list: ::prost::alloc::vec::Vec<ObjectId>,
 }
+#[derive(serde::Serialize)]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct MakeMoveVecPair {
@@ -506,7 +723,11 @@ pub struct MakeMoveVecPair {
     pub one: ::core::option::Option<::prost::alloc::string::String>,
     #[prost(message, repeated, tag = "2")]
     pub two: ::prost::alloc::vec::Vec<SuiArgument>,
+    /// The type in `one` parsed into a structured type tag
+    #[prost(message, optional, tag = "3")]
+    pub parsed_one: ::core::option::Option<ParsedTypeTag>,
 }
+#[derive(serde::Serialize)]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct SuiCommandUpgrade {
@@ -517,6 +738,7 @@ pub struct SuiCommandUpgrade {
     #[prost(message, optional, tag = "3")]
     pub three: ::core::option::Option<SuiArgument>,
 }
+#[derive(serde::Serialize)]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct SuiProgrammableMoveCall {
@@ -534,7 +756,32 @@ pub struct SuiProgrammableMoveCall {
     pub type_arguments: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
     #[prost(message, repeated, tag = "5")]
     pub arguments: ::prost::alloc::vec::Vec<SuiArgument>,
+    /// The type arguments parsed into structured type tags. It has the same length and order as `type_arguments`.
+    #[prost(message, repeated, tag = "6")]
+    pub parsed_type_arguments: ::prost::alloc::vec::Vec<ParsedTypeTag>,
+}
+#[derive(serde::Serialize)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ParsedTypeTag {
+    #[prost(oneof = "parsed_type_tag::ParsedTypeTag", tags = "1, 2")]
+    pub parsed_type_tag: ::core::option::Option<parsed_type_tag::ParsedTypeTag>,
+}
+/// Nested message and enum types in `ParsedTypeTag`.
+pub mod parsed_type_tag {
+    #[derive(serde::Serialize)]
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Oneof)]
+    pub enum ParsedTypeTag {
+        /// The Move type was parsed successfully
+        #[prost(message, tag = "1")]
+        TypeTag(super::TypeTag),
+        /// The Move type could not be parsed, and the reason why
+        #[prost(string, tag = "2")]
+        Error(::prost::alloc::string::String),
+    }
 }
+#[derive(serde::Serialize)]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct SuiArgument {
@@ -543,6 +790,7 @@ pub struct SuiArgument {
 }
 /// Nested message and enum types in `SuiArgument`.
 pub mod sui_argument {
+    #[derive(serde::Serialize)]
     #[allow(clippy::derive_partial_eq_without_eq)]
     #[derive(Clone, PartialEq, ::prost::Oneof)]
     pub enum SuiArguments {
@@ -563,6 +811,7 @@ pub mod sui_argument {
         NestedResult(super::PairOfU32),
     }
 }
+#[derive(serde::Serialize)]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct PairOfU32 {
@@ -571,6 +820,7 @@ pub struct PairOfU32 {
     #[prost(uint32, tag = "2")]
     pub two: u32,
 }
+#[derive(serde::Serialize)]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct SuiTransactionBlockEffects {
@@ -584,6 +834,7 @@ pub struct SuiTransactionBlockEffects {
 }
 /// Nested message and enum types in `SuiTransactionBlockEffects`.
 pub mod sui_transaction_block_effects {
+    #[derive(serde::Serialize)]
     #[allow(clippy::derive_partial_eq_without_eq)]
     #[derive(Clone, PartialEq, ::prost::Oneof)]
     pub enum SuiTransactionBlockEffects {
@@ -591,6 +842,7 @@ pub mod sui_transaction_block_effects {
         V1(super::SuiTransactionBlockEffectsV1),
     }
 }
+#[derive(serde::Serialize)]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct SuiTransactionBlockEffectsV1 {
@@ -646,6 +898,7 @@ pub struct SuiTransactionBlockEffectsV1 {
     #[prost(string, repeated, tag = "15")]
     pub dependencies: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
 }
+#[derive(serde::Serialize)]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct OwnedObjectRef {
@@ -654,6 +907,7 @@ pub struct OwnedObjectRef {
     #[prost(message, optional, tag = "2")]
     pub reference: ::core::option::Option<SuiObjectRef>,
 }
+#[derive(serde::Serialize)]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct Owner {
@@ -662,6 +916,7 @@ pub struct Owner {
 }
 /// Nested message and enum types in `Owner`.
 pub mod owner {
+    #[derive(serde::Serialize)]
     #[allow(clippy::derive_partial_eq_without_eq)]
     #[derive(Clone, PartialEq, ::prost::Oneof)]
     pub enum Owner {
@@ -680,6 +935,7 @@ pub mod owner {
         Immutable(()),
     }
 }
+#[derive(serde::Serialize)]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct Shared {
@@ -687,6 +943,7 @@ pub struct Shared {
     #[prost(uint64, tag = "1")]
     pub initial_shared_version: u64,
 }
+#[derive(serde::Serialize)]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct SuiExecutionStatus {
@@ -697,6 +954,7 @@ pub struct SuiExecutionStatus {
 }
 /// Nested message and enum types in `SuiExecutionStatus`.
 pub mod sui_execution_status {
+    #[derive(serde::Serialize)]
     #[allow(clippy::derive_partial_eq_without_eq)]
     #[derive(Clone, PartialEq, ::prost::Oneof)]
     pub enum SuiExecutionStatus {
@@ -708,12 +966,54 @@ pub mod sui_execution_status {
         Failure(super::Failure),
     }
 }
+#[derive(serde::Serialize)]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct Failure {
     #[prost(string, tag = "1")]
     pub error: ::prost::alloc::string::String,
+    /// The error above parsed into its components. Unset if the error does not follow the known format.
+    #[prost(message, optional, tag = "2")]
+    pub parsed_error: ::core::option::Option<ExecutionFailure>,
+}
+#[derive(serde::Serialize)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ExecutionFailure {
+    /// The execution error kind i.e. `InsufficientGas`, `MoveAbort`, `CommandArgumentError`
+    #[prost(string, tag = "1")]
+    pub kind: ::prost::alloc::string::String,
+    /// The index of the command that failed, if the failure can be attributed to a single command
+    #[prost(uint64, optional, tag = "2")]
+    pub command: ::core::option::Option<u64>,
+    /// The location in Move code where the failure happened. Present for `MoveAbort` and `MovePrimitiveRuntimeError`
+    #[prost(message, optional, tag = "3")]
+    pub location: ::core::option::Option<MoveLocation>,
+    /// The abort code. Present only for `MoveAbort`
+    #[prost(uint64, optional, tag = "4")]
+    pub abort_code: ::core::option::Option<u64>,
 }
+#[derive(serde::Serialize)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MoveLocation {
+    /// Hex encoded address of the package containing the module
+    #[prost(string, tag = "1")]
+    pub address: ::prost::alloc::string::String,
+    /// The module name
+    #[prost(string, tag = "2")]
+    pub module: ::prost::alloc::string::String,
+    /// The index of the function definition in the module
+    #[prost(uint32, tag = "3")]
+    pub function: u32,
+    /// The bytecode offset of the instruction within the function
+    #[prost(uint32, tag = "4")]
+    pub instruction: u32,
+    /// The function name, if known
+    #[prost(string, optional, tag = "5")]
+    pub function_name: ::core::option::Option<::prost::alloc::string::String>,
+}
+#[derive(serde::Serialize)]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct SuiTransactionBlockEffectsModifiedAtVersions {
@@ -722,12 +1022,14 @@ pub struct SuiTransactionBlockEffectsModifiedAtVersions {
     #[prost(uint64, tag = "2")]
     pub sequence_number: u64,
 }
+#[derive(serde::Serialize)]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct SuiTransactionBlockEvents {
     #[prost(message, repeated, tag = "1")]
     pub data: ::prost::alloc::vec::Vec<SuiEvent>,
 }
+#[derive(serde::Serialize)]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct SuiEvent {
@@ -760,6 +1062,7 @@ pub struct SuiEvent {
     #[prost(uint64, optional, tag = "8")]
     pub timestamp_ms: ::core::option::Option<u64>,
 }
+#[derive(serde::Serialize)]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct EventId {
@@ -768,6 +1071,7 @@ pub struct EventId {
     #[prost(uint64, tag = "2")]
     pub event_seq: u64,
 }
+#[derive(serde::Serialize)]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct ChangedObject {
@@ -776,6 +1080,7 @@ pub struct ChangedObject {
     #[prost(message, optional, tag = "2")]
     pub data: ::core::option::Option<SuiObjectData>,
 }
+#[derive(serde::Serialize)]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct ObjectStatus {
@@ -784,6 +1089,7 @@ pub struct ObjectStatus {
 }
 /// Nested message and enum types in `ObjectStatus`.
 pub mod object_status {
+    #[derive(serde::Serialize)]
     #[allow(clippy::derive_partial_eq_without_eq)]
     #[derive(Clone, PartialEq, ::prost::Oneof)]
     pub enum ObjectStatus {
@@ -801,6 +1107,7 @@ pub mod object_status {
         UnwrappedThenDeleted(()),
     }
 }
+#[derive(serde::Serialize)]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct SuiObjectData {
@@ -841,6 +1148,7 @@ pub struct SuiObjectData {
     #[prost(message, optional, tag = "10")]
     pub bcs: ::core::option::Option<SuiRawData>,
 }
+#[derive(serde::Serialize)]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct ObjectType {
@@ -849,6 +1157,7 @@ pub struct ObjectType {
 }
 /// Nested message and enum types in `ObjectType`.
 pub mod object_type {
+    #[derive(serde::Serialize)]
     #[allow(clippy::derive_partial_eq_without_eq)]
     #[derive(Clone, PartialEq, ::prost::Oneof)]
     pub enum ObjectType {
@@ -860,6 +1169,7 @@ pub mod object_type {
         Struct(super::MoveObjectType),
     }
 }
+#[derive(serde::Serialize)]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct MoveObjectType {
@@ -868,6 +1178,7 @@ pub struct MoveObjectType {
 }
 /// Nested message and enum types in `MoveObjectType`.
 pub mod move_object_type {
+    #[derive(serde::Serialize)]
     #[allow(clippy::derive_partial_eq_without_eq)]
     #[derive(Clone, PartialEq, ::prost::Oneof)]
     pub enum MoveObjectType {
@@ -885,6 +1196,7 @@ pub mod move_object_type {
         Coin(super::TypeTag),
     }
 }
+#[derive(serde::Serialize)]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct DisplayFieldsResponse {
@@ -896,6 +1208,7 @@ pub struct DisplayFieldsResponse {
     #[prost(message, optional, tag = "2")]
     pub error: ::core::option::Option<SuiObjectResponseError>,
 }
+#[derive(serde::Serialize)]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct SuiObjectResponseError {
@@ -909,18 +1222,21 @@ pub struct SuiObjectResponseError {
 }
 /// Nested message and enum types in `SuiObjectResponseError`.
 pub mod sui_object_response_error {
+    #[derive(serde::Serialize)]
     #[allow(clippy::derive_partial_eq_without_eq)]
     #[derive(Clone, PartialEq, ::prost::Message)]
     pub struct NotExists {
         #[prost(message, optional, tag = "1")]
         pub object_id: ::core::option::Option<super::ObjectId>,
     }
+    #[derive(serde::Serialize)]
     #[allow(clippy::derive_partial_eq_without_eq)]
     #[derive(Clone, PartialEq, ::prost::Message)]
     pub struct DynamicFieldNotFound {
         #[prost(message, optional, tag = "1")]
         pub parent_object_id: ::core::option::Option<super::ObjectId>,
     }
+    #[derive(serde::Serialize)]
     #[allow(clippy::derive_partial_eq_without_eq)]
     #[derive(Clone, PartialEq, ::prost::Message)]
     pub struct Deleted {
@@ -931,12 +1247,14 @@ pub mod sui_object_response_error {
         #[prost(string, tag = "3")]
         pub digest: ::prost::alloc::string::String,
     }
+    #[derive(serde::Serialize)]
     #[allow(clippy::derive_partial_eq_without_eq)]
     #[derive(Clone, PartialEq, ::prost::Message)]
     pub struct DisplayError {
         #[prost(string, tag = "1")]
         pub error: ::prost::alloc::string::String,
     }
+    #[derive(serde::Serialize)]
     #[allow(clippy::derive_partial_eq_without_eq)]
     #[derive(Clone, PartialEq, ::prost::Oneof)]
     pub enum SuiObjectResponseError {
@@ -952,6 +1270,7 @@ pub mod sui_object_response_error {
         DisplayError(DisplayError),
     }
 }
+#[derive(serde::Serialize)]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct SuiParsedData {
@@ -960,6 +1279,7 @@ pub struct SuiParsedData {
 }
 /// Nested message and enum types in `SuiParsedData`.
 pub mod sui_parsed_data {
+    #[derive(serde::Serialize)]
     #[allow(clippy::derive_partial_eq_without_eq)]
     #[derive(Clone, PartialEq, ::prost::Oneof)]
     pub enum SuiParsedData {
@@ -969,6 +1289,7 @@ pub mod sui_parsed_data {
         Package(super::SuiMovePackage),
     }
 }
+#[derive(serde::Serialize)]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct SuiParsedMoveObject {
@@ -979,6 +1300,7 @@ pub struct SuiParsedMoveObject {
     #[prost(message, optional, tag = "3")]
     pub fields: ::core::option::Option<SuiMoveStruct>,
 }
+#[derive(serde::Serialize)]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct SuiMoveStruct {
@@ -987,6 +1309,7 @@ pub struct SuiMoveStruct {
 }
 /// Nested message and enum types in `SuiMoveStruct`.
 pub mod sui_move_struct {
+    #[derive(serde::Serialize)]
     #[allow(clippy::derive_partial_eq_without_eq)]
     #[derive(Clone, PartialEq, ::prost::Oneof)]
     pub enum SuiMoveStruct {
@@ -998,12 +1321,14 @@ pub mod sui_move_struct {
         WithFields(super::WithFields),
     }
 }
+#[derive(serde::Serialize)]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct ListOfSuiMoveValues {
     #[prost(message, repeated, tag = "1")]
     pub list: ::prost::alloc::vec::Vec<SuiMoveValue>,
 }
+#[derive(serde::Serialize)]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct WithTypes {
@@ -1015,6 +1340,7 @@ pub struct WithTypes {
         SuiMoveValue,
     >,
 }
+#[derive(serde::Serialize)]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct WithFields {
@@ -1024,6 +1350,7 @@ pub struct WithFields {
         SuiMoveValue,
     >,
 }
+#[derive(serde::Serialize)]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct SuiMoveValue {
@@ -1032,6 +1359,7 @@ pub struct SuiMoveValue {
 }
 /// Nested message and enum types in `SuiMoveValue`.
 pub mod sui_move_value {
+    #[derive(serde::Serialize)]
     #[allow(clippy::derive_partial_eq_without_eq)]
     #[derive(Clone, PartialEq, ::prost::Oneof)]
     pub enum SuiMoveValue {
@@ -1053,12 +1381,14 @@ pub mod sui_move_value {
         Option(::prost::alloc::boxed::Box<super::SuiMoveValue>),
     }
 }
+#[derive(serde::Serialize)]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct Uid {
     #[prost(message, optional, tag = "1")]
     pub id: ::core::option::Option<ObjectId>,
 }
+#[derive(serde::Serialize)]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct SuiMovePackage {
@@ -1068,6 +1398,7 @@ pub struct SuiMovePackage {
         SuiJsonValue,
     >,
 }
+#[derive(serde::Serialize)]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct SuiRawData {
@@ -1076,6 +1407,7 @@ pub struct SuiRawData {
 }
 /// Nested message and enum types in `SuiRawData`.
 pub mod sui_raw_data {
+    #[derive(serde::Serialize)]
     #[allow(clippy::derive_partial_eq_without_eq)]
     #[derive(Clone, PartialEq, ::prost::Oneof)]
     pub enum SuiRawData {
@@ -1085,6 +1417,7 @@ pub mod sui_raw_data {
         Package(super::SuiRawMovePackage),
     }
 }
+#[derive(serde::Serialize)]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct SuiRawMoveObject {
@@ -1097,6 +1430,7 @@ pub struct SuiRawMoveObject {
     #[prost(bytes = "vec", tag = "4")]
     pub bcs_bytes: ::prost::alloc::vec::Vec<u8>,
 }
+#[derive(serde::Serialize)]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct SuiRawMovePackage {
@@ -1119,6 +1453,7 @@ pub struct SuiRawMovePackage {
         UpgradeInfo,
     >,
 }
+#[derive(serde::Serialize)]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct TypeOrigin {
@@ -1129,6 +1464,7 @@ pub struct TypeOrigin {
     #[prost(message, optional, tag = "3")]
     pub package: ::core::option::Option<ObjectId>,
 }
+#[derive(serde::Serialize)]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct UpgradeInfo {
@@ -1137,3 +1473,53 @@ pub struct UpgradeInfo {
     #[prost(uint64, tag = "2")]
     pub upgraded_version: u64,
 }
+#[derive(serde::Serialize)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum AddressMatchReason {
+    Unspecified = 0,
+    /// The address is the sender of the transaction
+    Sender = 1,
+    /// The address owns the gas payment
+    GasOwner = 2,
+    /// The address owns an object created by the transaction
+    CreatedObjectOwner = 3,
+    /// The address owns an object mutated by the transaction
+    MutatedObjectOwner = 4,
+    /// The address is the recipient of a `TransferObjects` command
+    TransferRecipient = 5,
+}
+impl AddressMatchReason {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            AddressMatchReason::Unspecified => "ADDRESS_MATCH_REASON_UNSPECIFIED",
+            AddressMatchReason::Sender => "ADDRESS_MATCH_REASON_SENDER",
+            AddressMatchReason::GasOwner => "ADDRESS_MATCH_REASON_GAS_OWNER",
+            AddressMatchReason::CreatedObjectOwner => {
+                "ADDRESS_MATCH_REASON_CREATED_OBJECT_OWNER"
+            }
+            AddressMatchReason::MutatedObjectOwner => {
+                "ADDRESS_MATCH_REASON_MUTATED_OBJECT_OWNER"
+            }
+            AddressMatchReason::TransferRecipient => {
+                "ADDRESS_MATCH_REASON_TRANSFER_RECIPIENT"
+            }
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "ADDRESS_MATCH_REASON_UNSPECIFIED" => Some(Self::Unspecified),
+            "ADDRESS_MATCH_REASON_SENDER" => Some(Self::Sender),
+            "ADDRESS_MATCH_REASON_GAS_OWNER" => Some(Self::GasOwner),
+            "ADDRESS_MATCH_REASON_CREATED_OBJECT_OWNER" => Some(Self::CreatedObjectOwner),
+            "ADDRESS_MATCH_REASON_MUTATED_OBJECT_OWNER" => Some(Self::MutatedObjectOwner),
+            "ADDRESS_MATCH_REASON_TRANSFER_RECIPIENT" => Some(Self::TransferRecipient),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,338 @@
+use std::{fmt, future::Future, str::FromStr};
+use chrono::{DateTime, Utc};
+use eyre::{Result, Report};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use crate::checkpoint_handler::CheckpointHandler;
+
+/// Where to start or stop streaming. Everything but a checkpoint sequence number is resolved against the RPC node
+/// once it is ready.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CheckpointPosition {
+  /// A checkpoint sequence number i.e. `1234`
+  Seq(u64),
+  /// An RFC 3339 timestamp i.e. `2023-03-01T00:00:00Z`
+  Timestamp(DateTime<Utc>),
+  /// An epoch number i.e. `epoch:120`
+  Epoch(u64),
+  /// The latest checkpoint known to the RPC node at startup
+  Latest,
+}
+
+impl Default for CheckpointPosition {
+  fn default() -> Self {
+    CheckpointPosition::Seq(0)
+  }
+}
+
+impl FromStr for CheckpointPosition {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    if s == "latest" {
+      return Ok(CheckpointPosition::Latest)
+    }
+
+    if let Some(epoch) = s.strip_prefix("epoch:") {
+      return epoch
+      .parse()
+      .map(CheckpointPosition::Epoch)
+      .map_err(|e| format!("invalid epoch {}: {}", epoch, e))
+    }
+
+    if let Ok(seq) = s.parse() {
+      return Ok(CheckpointPosition::Seq(seq))
+    }
+
+    DateTime::parse_from_rfc3339(s)
+    .map(|timestamp| CheckpointPosition::Timestamp(timestamp.with_timezone(&Utc)))
+    .map_err(|_| {
+      format!(
+        "invalid position {}, expected a checkpoint sequence number, epoch:<n>, latest or an RFC 3339 timestamp",
+        s,
+      )
+    })
+  }
+}
+
+impl fmt::Display for CheckpointPosition {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      CheckpointPosition::Seq(seq) => write!(f, "{}", seq),
+      CheckpointPosition::Timestamp(timestamp) => write!(f, "{}", timestamp.to_rfc3339()),
+      CheckpointPosition::Epoch(epoch) => write!(f, "epoch:{}", epoch),
+      CheckpointPosition::Latest => write!(f, "latest"),
+    }
+  }
+}
+
+/// Sequence numbers are kept as TOML integers, so that existing config files still load
+impl Serialize for CheckpointPosition {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    match self {
+      CheckpointPosition::Seq(seq) => serializer.serialize_u64(*seq),
+      _ => serializer.serialize_str(&self.to_string()),
+    }
+  }
+}
+
+impl<'de> Deserialize<'de> for CheckpointPosition {
+  fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Repr {
+      Seq(u64),
+      Str(String),
+    }
+
+    match Repr::deserialize(deserializer)? {
+      Repr::Seq(seq) => Ok(CheckpointPosition::Seq(seq)),
+      Repr::Str(s) => s.parse().map_err(serde::de::Error::custom),
+    }
+  }
+}
+
+impl CheckpointPosition {
+  /// The first checkpoint to stream: the first one at or after the timestamp, or the first one of the epoch
+  pub async fn resolve_start(&self, checkpoint_handler: &CheckpointHandler) -> Result<u64> {
+    self.try_resolve_start(checkpoint_handler).await?.ok_or_else(|| match self {
+      CheckpointPosition::Epoch(epoch) => Report::msg(format!("Epoch {} has not started yet", epoch)),
+      _ => Report::msg(format!("The RPC node has no checkpoint at or after {}", self)),
+    })
+  }
+
+  /// The last checkpoint to stream, inclusive: the last one at or before the timestamp, or the last one of the
+  /// epoch. Fails if that checkpoint is not known yet as the stop would be ambiguous.
+  pub async fn resolve_stop(&self, checkpoint_handler: &CheckpointHandler) -> Result<u64> {
+    self.try_resolve_stop(checkpoint_handler).await?.ok_or_else(|| match self {
+      CheckpointPosition::Epoch(epoch) => Report::msg(format!("Epoch {} has not ended yet", epoch)),
+      _ => Report::msg(format!("The RPC node has not reached {} yet", self)),
+    })
+  }
+
+  /// Like `resolve_start`, but returns `None` if the RPC node has not reached the position yet
+  pub async fn try_resolve_start(&self, checkpoint_handler: &CheckpointHandler) -> Result<Option<u64>> {
+    if let CheckpointPosition::Seq(seq) = self {
+      return Ok(Some(*seq))
+    }
+
+    let latest = checkpoint_handler.get_latest_checkpoint_sequence_number().await?;
+    self.start_in(latest, |seq| checkpoint_keys(checkpoint_handler, seq)).await
+  }
+
+  /// Like `resolve_stop`, but returns `None` if the RPC node has not reached the position yet
+  pub async fn try_resolve_stop(&self, checkpoint_handler: &CheckpointHandler) -> Result<Option<u64>> {
+    if let CheckpointPosition::Seq(seq) = self {
+      return Ok(Some(*seq))
+    }
+
+    let latest = checkpoint_handler.get_latest_checkpoint_sequence_number().await?;
+    self.stop_in(latest, |seq| checkpoint_keys(checkpoint_handler, seq)).await
+  }
+
+  /// Whether a checkpoint with this timestamp and epoch comes after the stop position. A stop the RPC node has not
+  /// reached yet can't be resolved, so it is detected on the first checkpoint past it instead.
+  pub fn is_past_stop(&self, timestamp_ms: u64, epoch: u64) -> bool {
+    match self {
+      CheckpointPosition::Seq(_) | CheckpointPosition::Latest => false,
+      CheckpointPosition::Timestamp(timestamp) => timestamp_ms > timestamp.timestamp_millis().max(0) as u64,
+      CheckpointPosition::Epoch(stop_epoch) => epoch > *stop_epoch,
+    }
+  }
+
+  async fn start_in<F: Future<Output = Result<CheckpointKeys>>>(
+    &self,
+    latest: u64,
+    checkpoint: impl Fn(u64) -> F,
+  ) -> Result<Option<u64>> {
+    match self {
+      CheckpointPosition::Seq(seq) => Ok(Some(*seq)),
+      CheckpointPosition::Latest => Ok(Some(latest)),
+      CheckpointPosition::Timestamp(timestamp) => {
+        let timestamp_ms = timestamp.timestamp_millis().max(0) as u64;
+        first_matching(latest, timestamp_ms, checkpoint, |c| c.timestamp_ms).await
+      }
+      CheckpointPosition::Epoch(epoch) => first_matching(latest, *epoch, checkpoint, |c| c.epoch).await,
+    }
+  }
+
+  async fn stop_in<F: Future<Output = Result<CheckpointKeys>>>(
+    &self,
+    latest: u64,
+    checkpoint: impl Fn(u64) -> F,
+  ) -> Result<Option<u64>> {
+    let next = match self {
+      CheckpointPosition::Seq(seq) => return Ok(Some(*seq)),
+      CheckpointPosition::Latest => return Ok(Some(latest)),
+      CheckpointPosition::Timestamp(timestamp) => {
+        let timestamp_ms = timestamp.timestamp_millis().max(0) as u64;
+        first_matching(latest, timestamp_ms.saturating_add(1), checkpoint, |c| c.timestamp_ms).await?
+      }
+      CheckpointPosition::Epoch(epoch) => {
+        first_matching(latest, epoch.saturating_add(1), checkpoint, |c| c.epoch).await?
+      }
+    };
+
+    match next {
+      Some(next) => next
+      .checked_sub(1)
+      .map(Some)
+      .ok_or_else(|| Report::msg(format!("There is no checkpoint before {}", self))),
+      None => Ok(None),
+    }
+  }
+}
+
+/// The parts of a checkpoint positions are resolved with
+#[derive(Debug, Clone, Copy)]
+struct CheckpointKeys {
+  timestamp_ms: u64,
+  epoch: u64,
+}
+
+async fn checkpoint_keys(checkpoint_handler: &CheckpointHandler, seq: u64) -> Result<CheckpointKeys> {
+  let checkpoint = checkpoint_handler.get_checkpoint(seq).await?;
+
+  Ok(CheckpointKeys {
+    timestamp_ms: checkpoint.timestamp_ms,
+    epoch: checkpoint.epoch,
+  })
+}
+
+/// Binary searches the first checkpoint up to `latest` for which `key` is at least `target`. `key` must not
+/// decrease with the sequence number, which holds for timestamps and epochs.
+async fn first_matching<F: Future<Output = Result<CheckpointKeys>>>(
+  latest: u64,
+  target: u64,
+  checkpoint: impl Fn(u64) -> F,
+  key: impl Fn(&CheckpointKeys) -> u64,
+) -> Result<Option<u64>> {
+  let (mut low, mut high) = (0, latest + 1);
+
+  while low < high {
+    let mid = low + (high - low) / 2;
+
+    if key(&checkpoint(mid).await?) >= target {
+      high = mid;
+    } else {
+      low = mid + 1;
+    }
+  }
+
+  Ok(Some(low).filter(|seq| *seq <= latest))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::future::{ready, Ready};
+
+  /// The timestamp and epoch of checkpoints 0 to 5. Epoch 2 has not ended yet.
+  const CHECKPOINTS: [(u64, u64); 6] = [(1_000, 0), (2_000, 0), (3_000, 1), (4_000, 1), (5_000, 2), (6_000, 2)];
+  const LATEST: u64 = 5;
+
+  fn checkpoint(seq: u64) -> Ready<Result<CheckpointKeys>> {
+    let (timestamp_ms, epoch) = CHECKPOINTS[seq as usize];
+    ready(Ok(CheckpointKeys {timestamp_ms, epoch}))
+  }
+
+  fn position(source: &str) -> CheckpointPosition {
+    source.parse().unwrap()
+  }
+
+  async fn start(source: &str) -> Option<u64> {
+    position(source).start_in(LATEST, checkpoint).await.unwrap()
+  }
+
+  async fn stop(source: &str) -> Result<Option<u64>> {
+    position(source).stop_in(LATEST, checkpoint).await
+  }
+
+  /// An RFC 3339 timestamp `ms` milliseconds after the epoch
+  fn at(ms: u64) -> String {
+    DateTime::<Utc>::from(std::time::UNIX_EPOCH + std::time::Duration::from_millis(ms)).to_rfc3339()
+  }
+
+  #[test]
+  fn parses_positions() {
+    assert_eq!(position("12"), CheckpointPosition::Seq(12));
+    assert_eq!(position("epoch:12"), CheckpointPosition::Epoch(12));
+    assert_eq!(position("latest"), CheckpointPosition::Latest);
+    assert_eq!(
+      position("2023-03-01T00:00:00Z"),
+      CheckpointPosition::Timestamp(DateTime::parse_from_rfc3339("2023-03-01T00:00:00Z").unwrap().into()),
+    );
+
+    assert!("epoch:".parse::<CheckpointPosition>().unwrap_err().starts_with("invalid epoch"));
+    assert!("epoch:x".parse::<CheckpointPosition>().unwrap_err().starts_with("invalid epoch x"));
+    assert!("epoch12".parse::<CheckpointPosition>().unwrap_err().starts_with("invalid position epoch12"));
+    assert!("-1".parse::<CheckpointPosition>().is_err());
+  }
+
+  #[test]
+  fn displays_positions_as_they_are_parsed() {
+    for source in ["12", "epoch:12", "latest", "2023-03-01T00:00:00+00:00"] {
+      assert_eq!(position(source).to_string(), source);
+    }
+  }
+
+  #[tokio::test]
+  async fn resolves_epochs() {
+    assert_eq!(start("epoch:0").await, Some(0));
+    assert_eq!(start("epoch:1").await, Some(2));
+    assert_eq!(start("epoch:2").await, Some(4));
+    assert_eq!(start("epoch:3").await, None);
+
+    assert_eq!(stop("epoch:0").await.unwrap(), Some(1));
+    assert_eq!(stop("epoch:1").await.unwrap(), Some(3));
+    // The last checkpoint of an epoch is only known once the next one has started
+    assert_eq!(stop("epoch:2").await.unwrap(), None);
+  }
+
+  #[tokio::test]
+  async fn resolves_timestamps() {
+    // Exactly on a checkpoint, both positions resolve to that checkpoint
+    assert_eq!(start(&at(3_000)).await, Some(2));
+    assert_eq!(stop(&at(3_000)).await.unwrap(), Some(2));
+
+    // Between two checkpoints, the start is the next one and the stop the previous one
+    assert_eq!(start(&at(3_500)).await, Some(3));
+    assert_eq!(stop(&at(3_500)).await.unwrap(), Some(2));
+
+    assert_eq!(start(&at(0)).await, Some(0));
+    assert_eq!(start(&at(6_001)).await, None);
+    // More checkpoints may still share the timestamp of the latest one
+    assert_eq!(stop(&at(6_000)).await.unwrap(), None);
+  }
+
+  #[tokio::test]
+  async fn fails_to_stop_before_the_first_checkpoint() {
+    let error = stop(&at(999)).await.unwrap_err();
+    assert!(error.to_string().starts_with("There is no checkpoint before"), "{}", error);
+  }
+
+  /// The first checkpoint past a stop is the one after the checkpoint the stop resolves to
+  #[tokio::test]
+  async fn detects_checkpoints_past_the_stop() {
+    for source in ["epoch:0", "epoch:1", at(3_000).as_str(), at(3_500).as_str()] {
+      let stop_position = position(source);
+      let past_stop = CHECKPOINTS
+      .iter()
+      .position(|(timestamp_ms, epoch)| stop_position.is_past_stop(*timestamp_ms, *epoch))
+      .map(|seq| seq as u64);
+
+      assert_eq!(past_stop.map(|seq| seq - 1), stop(source).await.unwrap(), "{}", source);
+    }
+
+    // Neither has been reached by any checkpoint yet
+    assert!(!CHECKPOINTS.iter().any(|(timestamp_ms, epoch)| position("epoch:2").is_past_stop(*timestamp_ms, *epoch)));
+    assert!(!CHECKPOINTS.iter().any(|(timestamp_ms, epoch)| position(&at(6_000)).is_past_stop(*timestamp_ms, *epoch)));
+  }
+
+  #[tokio::test]
+  async fn resolves_sequence_numbers_and_latest() {
+    assert_eq!(start("3").await, Some(3));
+    assert_eq!(stop("3").await.unwrap(), Some(3));
+    assert_eq!(start("latest").await, Some(LATEST));
+    assert_eq!(stop("latest").await.unwrap(), Some(LATEST));
+  }
+}
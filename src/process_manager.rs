@@ -1,129 +1,552 @@
 use std::{
-  sync::{mpsc::sync_channel, Arc, Mutex}, panic, process, mem
+  sync::{Arc, Mutex}, io::{self, Write}, net::SocketAddr, time::Duration, panic, process, mem
 };
 use ctrlc;
-use log::{info};
+use eyre::{Result, Report};
+use log::{error, info};
 use tokio::{
   spawn, sync::{
-    oneshot::{channel, Sender},
+    oneshot::{channel, Sender}, mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender}, watch,
   }, task::JoinHandle,
 };
-use clap::{Parser};
-use crate::{sui_node::SuiNode, runtime::FirehoseStreamer};
+use clap::{Parser, Subcommand};
+use crate::{
+  sui_node::{SuiNode, read_rpc_url}, runtime::FirehoseStreamer, projection::{OutputProfile, Field},
+  config::{Config, RestartPolicy, LogFormat}, server, telemetry, health::poll_network_tip,
+  fetch::{fetch, FetchFormat}, inspect::inspect, position::CheckpointPosition,
+  processor::{CheckpointProcessor, ErrorPolicy, Processors},
+  filter::{
+    expr::FilterExpr,
+    rules::{ModulePattern, FunctionPattern, TypePattern, parse_address},
+  },
+};
 
+/// Every argument overrides the matching setting of the config file. Most of them can also be set through
+/// a `SUI_SF_*` environment variable, which takes precedence over the config file but not over the flag.
 #[derive(Default, Debug, Parser)]
 struct Args {
-  /// The fullnode config file
-  #[arg(short = 'c', long)]
-  sui_node_config: String,
+  #[command(subcommand)]
+  command: Option<Command>,
+
+  /// A TOML config file. See the README for the available settings
+  #[arg(long, env = "SUI_SF_CONFIG")]
+  config: Option<String>,
+
+  /// Print the effective configuration as TOML and exit
+  #[arg(long)]
+  print_config: bool,
+
+  /// The fullnode config file. Required if no rpc client url is provided
+  #[arg(short = 'c', long, env = "SUI_SF_NODE_CONFIG")]
+  sui_node_config: Option<String>,
+
+  /// The sui-node binary to run when no rpc client url is provided
+  #[arg(long, env = "SUI_SF_NODE_BINARY")]
+  sui_node_binary: Option<String>,
+
+  /// An extra argument passed to the sui-node i.e. --sui-node-arg=--foo. Can be repeated
+  #[arg(long, allow_hyphen_values = true)]
+  sui_node_arg: Vec<String>,
+
+  /// Write the sui-node output to this file instead of stderr
+  #[arg(long, env = "SUI_SF_NODE_LOG_PATH")]
+  sui_node_log_path: Option<String>,
+
+  /// When to restart the local sui-node after it exits
+  #[arg(long, env = "SUI_SF_NODE_RESTART_POLICY", value_enum)]
+  node_restart_policy: Option<RestartPolicy>,
+
+  /// Give up after this many consecutive restarts of the local sui-node, or never if 0
+  #[arg(long, env = "SUI_SF_NODE_MAX_RESTARTS")]
+  node_max_restarts: Option<u32>,
+
+  /// Switch to this RPC url when streaming stalls. Can be repeated
+  #[arg(long, env = "SUI_SF_RPC_FALLBACK_URL", value_delimiter = ',')]
+  rpc_fallback_url: Vec<String>,
+
+  /// Recover once no checkpoint has been emitted for this many seconds. 0 disables the watchdog
+  #[arg(long, env = "SUI_SF_WATCHDOG_STALL_TIMEOUT_SECS")]
+  watchdog_stall_timeout_secs: Option<u64>,
+
+  /// Log as plain text or as JSON objects, to stderr
+  #[arg(long, env = "SUI_SF_LOG_FORMAT", value_enum)]
+  log_format: Option<LogFormat>,
+
+  /// Export the spans to this OpenTelemetry collector i.e. http://localhost:4317
+  #[arg(long, env = "SUI_SF_OTLP_ENDPOINT")]
+  otlp_endpoint: Option<String>,
+
+  /// Serve the Prometheus metrics and the /healthz and /readyz probes on this address i.e. 0.0.0.0:9102
+  #[arg(long, env = "SUI_SF_HTTP_LISTEN_ADDRESS")]
+  http_listen_address: Option<SocketAddr>,
 
   /// Chain Identifier is the digest of the genesis checkpoint
-  #[arg(short = 'i', long, default_value = "4btiuiMPvEENsttpZC7CZ53DruC3MAgfznDbASZ7DR6S")]
-  chain_id: String,
+  #[arg(short = 'i', long, env = "SUI_SF_CHAIN_ID")]
+  chain_id: Option<String>,
+
+  /// Which checkpoint should we start streaming data from: a sequence number, epoch:<n>, an RFC 3339 timestamp
+  /// i.e. 2023-03-01T00:00:00Z, or latest
+  #[arg(short = 's', long, alias = "start", env = "SUI_SF_STARTING_CHECKPOINT_SEQ")]
+  starting_checkpoint_seq: Option<CheckpointPosition>,
 
-  /// Which checkount should we start streaming data from
-  #[arg(short = 's', long, default_value_t = 0)]
-  starting_checkpoint_seq: u64,
+  /// The last checkpoint to stream, inclusive: a sequence number, epoch:<n>, an RFC 3339 timestamp, or latest.
+  /// The process exits once it has been emitted
+  #[arg(long, alias = "stop", env = "SUI_SF_STOP_CHECKPOINT_SEQ")]
+  stop_checkpoint_seq: Option<CheckpointPosition>,
+
+  /// A file where the next checkpoint to stream is saved on exit, and resumed from on startup
+  #[arg(long, env = "SUI_SF_CURSOR_PATH")]
+  cursor_path: Option<String>,
 
   /// You can use https://fullnode.mainnet.sui.io:443 for mainnet
   /// Note that if one is not provided, a local sui-node will be spinned up instead
-  #[arg(short = 'r', long)]
+  #[arg(short = 'r', long, env = "SUI_SF_RPC_CLIENT_URL")]
   rpc_client_url: Option<String>,
+
+  /// The initial delay before retrying a failed RPC call
+  #[arg(long, env = "SUI_SF_RETRY_INITIAL_INTERVAL_MS")]
+  retry_initial_interval_ms: Option<u64>,
+
+  /// The maximum delay between two retries of a failed RPC call
+  #[arg(long, env = "SUI_SF_RETRY_MAX_INTERVAL_MS")]
+  retry_max_interval_ms: Option<u64>,
+
+  /// The factor the delay is multiplied by after each retry
+  #[arg(long, env = "SUI_SF_RETRY_MULTIPLIER")]
+  retry_multiplier: Option<f64>,
+
+  /// Give up retrying a failed RPC call after this many seconds, or never if 0
+  #[arg(long, env = "SUI_SF_RETRY_MAX_ELAPSED_TIME_SECS")]
+  retry_max_elapsed_time_secs: Option<u64>,
+
+  /// Only emit transactions calling or emitting events from this package i.e. 0x2. Can be repeated
+  #[arg(long, value_parser = parse_address)]
+  include_package: Vec<String>,
+
+  /// Only emit transactions calling or emitting events from this module i.e. 0x2::coin. Can be repeated
+  #[arg(long)]
+  include_module: Vec<ModulePattern>,
+
+  /// Only emit transactions calling this function i.e. 0x2::coin::split. Can be repeated
+  #[arg(long)]
+  include_function: Vec<FunctionPattern>,
+
+  /// Only emit transactions emitting events of this type i.e. 0x2::coin::CoinMetadata. Can be repeated
+  #[arg(long)]
+  include_event_type: Vec<TypePattern>,
+
+  /// Only emit changed objects of this Move type i.e. 0x2::coin::Coin<0x2::sui::SUI>. Can be repeated
+  #[arg(long)]
+  include_object_type: Vec<TypePattern>,
+
+  /// Do not emit transactions calling or emitting events from this package. Can be repeated
+  #[arg(long, value_parser = parse_address)]
+  exclude_package: Vec<String>,
+
+  /// Do not emit transactions calling or emitting events from this module. Can be repeated
+  #[arg(long)]
+  exclude_module: Vec<ModulePattern>,
+
+  /// Do not emit transactions calling this function. Can be repeated
+  #[arg(long)]
+  exclude_function: Vec<FunctionPattern>,
+
+  /// Do not emit transactions emitting events of this type. Can be repeated
+  #[arg(long)]
+  exclude_event_type: Vec<TypePattern>,
+
+  /// Do not emit changed objects of this Move type. Can be repeated
+  #[arg(long)]
+  exclude_object_type: Vec<TypePattern>,
+
+  /// Only emit transactions where this address is the sender, the gas owner, the owner of a created or mutated
+  /// object or the recipient of a transfer. Emitted transactions are tagged with the matching addresses.
+  /// Can be repeated
+  #[arg(long, env = "SUI_SF_WATCH_ADDRESS", value_delimiter = ',', value_parser = parse_address)]
+  watch_address: Vec<String>,
+
+  /// Only emit transactions matching this expression i.e. 'sender == 0x2a && move_call("0x2::coin::*")'.
+  /// See the README for the syntax
+  #[arg(long, env = "SUI_SF_FILTER_EXPR")]
+  filter_expr: Option<FilterExpr>,
+
+  /// The set of transaction and object fields to emit. Fields that are not emitted are not fetched either.
+  /// Defaults to lossless
+  #[arg(long, env = "SUI_SF_OUTPUT_PROFILE", value_enum)]
+  output_profile: Option<OutputProfile>,
+
+  /// Emit this field on top of the ones in the output profile. Can be repeated
+  #[arg(long, env = "SUI_SF_WITH_FIELD", value_delimiter = ',', value_enum)]
+  with_field: Vec<Field>,
+
+  /// Do not emit this field even if it is part of the output profile. Can be repeated
+  #[arg(long, env = "SUI_SF_WITHOUT_FIELD", value_delimiter = ',', value_enum)]
+  without_field: Vec<Field>,
+}
+
+#[derive(Debug, Clone, Subcommand)]
+enum Command {
+  /// Download a single checkpoint, convert it and print it to stdout instead of streaming. Uses the same config
+  /// and arguments as streaming, except that the local sui-node is not started
+  Fetch {
+    /// The sequence number of the checkpoint
+    seq: u64,
+
+    #[arg(long, value_enum, default_value_t = FetchFormat::Json)]
+    format: FetchFormat,
+  },
+  /// Decode FIRE lines i.e. captured reader node logs, check their framing and print them as JSON. Fails if any
+  /// line is invalid
+  Inspect {
+    /// The file to read, stdin if omitted
+    path: Option<String>,
+  },
+}
+
+impl Command {
+  /// Whether the command talks to an RPC node, which has to be configured then
+  fn needs_rpc(&self) -> bool {
+    match self {
+      Command::Fetch {..} => true,
+      Command::Inspect {..} => false,
+    }
+  }
+}
+
+fn override_with<T>(target: &mut T, source: Option<T>) {
+  if let Some(source) = source {
+    *target = source;
+  }
+}
+
+fn override_vec_with<T>(target: &mut Vec<T>, source: Vec<T>) {
+  if !source.is_empty() {
+    *target = source;
+  }
+}
+
+impl Args {
+  /// Loads the config file, if any, and applies the arguments on top of it
+  fn into_config(self) -> Result<Config> {
+    let mut config = Config::load(self.config.as_deref())?;
+
+    override_with(&mut config.chain_id, self.chain_id);
+    override_with(&mut config.starting_checkpoint_seq, self.starting_checkpoint_seq);
+    config.stop_checkpoint_seq = self.stop_checkpoint_seq.or(config.stop_checkpoint_seq);
+    config.cursor_path = self.cursor_path.or(config.cursor_path);
+    config.rpc.url = self.rpc_client_url.or(config.rpc.url);
+    override_vec_with(&mut config.rpc.fallback_urls, self.rpc_fallback_url);
+    override_with(&mut config.watchdog.stall_timeout_secs, self.watchdog_stall_timeout_secs);
+    override_with(&mut config.log.format, self.log_format);
+    config.log.otlp_endpoint = self.otlp_endpoint.or(config.log.otlp_endpoint);
+    config.http.listen_address = self.http_listen_address.or(config.http.listen_address);
+    config.node.config_path = self.sui_node_config.or(config.node.config_path);
+    override_with(&mut config.node.binary, self.sui_node_binary);
+    override_vec_with(&mut config.node.args, self.sui_node_arg);
+    config.node.log_path = self.sui_node_log_path.or(config.node.log_path);
+    override_with(&mut config.node.restart.policy, self.node_restart_policy);
+    override_with(&mut config.node.restart.max_attempts, self.node_max_restarts);
+
+    override_with(&mut config.retry.initial_interval_ms, self.retry_initial_interval_ms);
+    override_with(&mut config.retry.max_interval_ms, self.retry_max_interval_ms);
+    override_with(&mut config.retry.multiplier, self.retry_multiplier);
+    override_with(&mut config.retry.max_elapsed_time_secs, self.retry_max_elapsed_time_secs);
+
+    override_with(&mut config.output.profile, self.output_profile);
+    override_vec_with(&mut config.output.with_fields, self.with_field);
+    override_vec_with(&mut config.output.without_fields, self.without_field);
+
+    let filter = &mut config.filter;
+    override_vec_with(&mut filter.watch_addresses, self.watch_address);
+    filter.expression = self.filter_expr.or(filter.expression.take());
+    override_vec_with(&mut filter.include.packages, self.include_package);
+    override_vec_with(&mut filter.include.modules, self.include_module);
+    override_vec_with(&mut filter.include.functions, self.include_function);
+    override_vec_with(&mut filter.include.event_types, self.include_event_type);
+    override_vec_with(&mut filter.include.object_types, self.include_object_type);
+    override_vec_with(&mut filter.exclude.packages, self.exclude_package);
+    override_vec_with(&mut filter.exclude.modules, self.exclude_module);
+    override_vec_with(&mut filter.exclude.functions, self.exclude_function);
+    override_vec_with(&mut filter.exclude.event_types, self.exclude_event_type);
+    override_vec_with(&mut filter.exclude.object_types, self.exclude_object_type);
+
+    Ok(config)
+  }
+}
+
+fn check_rpc(config: &Config) -> Result<()> {
+  if config.rpc.url.is_none() && config.node.config_path.is_none() {
+    return Err(Report::msg("Either a sui-node config or an rpc client url must be provided"))
+  }
+
+  Ok(())
+}
+
+/// Why the process is exiting
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Exit {
+  /// SIGINT or SIGTERM was received
+  Signal,
+  /// The streamer has returned i.e. the stop checkpoint has been emitted
+  Completed,
+  /// The streamer failed or a task panicked
+  Failure,
+}
+
+impl Exit {
+  /// A clean stop exits with 0 and an error with 1
+  pub fn code(&self) -> i32 {
+    match self {
+      Exit::Signal | Exit::Completed => 0,
+      Exit::Failure => 1,
+    }
+  }
 }
 
-#[derive(Default)]
 struct ProcessManagerInner {
-  args: Args,
-  tasks: Vec<Sender<()>>,
+  config: Config,
+  command: Option<Command>,
+  processors: Processors,
+  tasks: Vec<(Sender<()>, JoinHandle<()>)>,
+  handle_signals: bool,
+  exit_tx: UnboundedSender<Exit>,
+  /// Taken once streaming starts
+  exit_rx: Option<UnboundedReceiver<Exit>>,
 }
 
 pub struct ProcessManager(Arc<Mutex<ProcessManagerInner>>);
 
 impl ProcessManager {
-  pub fn new() -> Self {
-    let args = Args::parse();
-    let pm = ProcessManagerInner {args, tasks: Vec::new(),};
+  /// Runs the streamer with the given config. Logging is left to the caller, see `telemetry::init`.
+  pub fn new(config: Config) -> Result<Self> {
+    check_rpc(&config)?;
+
+    Ok(Self::with_command(config, None))
+  }
+
+  fn with_command(config: Config, command: Option<Command>) -> Self {
+    let (exit_tx, exit_rx) = unbounded_channel();
+    let pm = ProcessManagerInner {
+      config,
+      command,
+      processors: Processors::default(),
+      tasks: Vec::new(),
+      handle_signals: false,
+      exit_tx,
+      exit_rx: Some(exit_rx),
+    };
 
     ProcessManager(Arc::new(Mutex::new(pm)))
   }
 
-  fn register_hooks(&mut self)  {
-    let (tx, rx) = sync_channel(2);
+  /// Parses the CLI arguments, runs the `--print-config` and sets up logging
+  pub fn from_args() -> Result<Self> {
+    let mut args = Args::parse();
+    let command = args.command.take();
+    let print_config = args.print_config;
+    let config = args.into_config()?;
+
+    if command.as_ref().map(Command::needs_rpc).unwrap_or(true) {
+      check_rpc(&config)?;
+    }
+
+    let config_toml = config.to_toml()?;
+
+    if print_config {
+      print!("{}", config_toml);
+      process::exit(0);
+    }
+
+    telemetry::init(&config.log)?;
+    info!("Effective configuration:\n{}", config_toml);
+
+    Ok(Self::with_command(config, command))
+  }
+
+  /// Runs `processor` on every checkpoint before it is emitted, after the processors added before it
+  pub fn add_processor(&mut self, processor: impl CheckpointProcessor + 'static, error_policy: ErrorPolicy) {
+    self.0.lock().unwrap().processors.register(Box::new(processor), error_policy);
+  }
+
+  /// Makes `start` install a panic hook and a SIGINT and SIGTERM handler, which are process-wide, to stop streaming
+  /// the same way `stop` does. Meant for the binary, while embedders usually handle signals themselves.
+  pub fn handle_signals(&mut self) {
+    self.0.lock().unwrap().handle_signals = true;
+  }
+
+  /// Finishes the checkpoint being printed and stops streaming, as SIGINT does. Calling it again stops right away.
+  pub fn stop(&self) {
+    let _ = self.0.lock().unwrap().exit_tx.send(Exit::Signal);
+  }
+
+  fn register_hooks(tx: UnboundedSender<Exit>) -> Result<()> {
     let tx_2 = tx.clone();
     let orig_hook = panic::take_hook();
 
     // this hook will be called if any of the threads panics
     panic::set_hook(Box::new(move |panic_info| {
-      tx_2.send(()).expect("send msg");
+      let _ = tx_2.send(Exit::Failure);
       orig_hook(panic_info);
     }));
 
-    let tx_3 = tx.clone();
+    // the termination feature makes this handle SIGTERM as well as SIGINT
     ctrlc::set_handler(move || {
-      tx_3.send(()).expect("send msg");
-    }).unwrap();
-
-    let _ = rx.recv();
-    self.kill_all();
+      let _ = tx.send(Exit::Signal);
+    }).map_err(|e| Report::msg(format!("Failed to set the signal handler: {}", e)))
   }
 
-  pub async fn start(&mut self) {
-    let mut tasks = vec![];
+  /// Runs the command, or streams until the stop checkpoint has been emitted, streaming fails or it is stopped.
+  /// Returns how it ended, exiting the process with `Exit::code` being left to the caller.
+  pub async fn start(&mut self) -> Result<Exit> {
+    let (config, command, handle_signals) = {
+      let pm = self.0.lock().unwrap();
+      (pm.config.clone(), pm.command.clone(), pm.handle_signals)
+    };
+
+    match command {
+      Some(Command::Fetch {seq, format}) => {
+        let rpc_client_url = match &config.rpc.url {
+          Some(rpc_client_url) => rpc_client_url.clone(),
+          None => read_rpc_url(config.node.config_path.as_deref().expect("sui-node config should be provided"))?,
+        };
 
-    let pm = Arc::clone(&self.0);
-    let rpc_client_url = pm.lock().unwrap().args.rpc_client_url.clone();
+        let result = fetch(rpc_client_url, &config, seq, format).await;
+        telemetry::shutdown();
+
+        return result.map(|_| Exit::Completed)
+      }
+      Some(Command::Inspect {path}) => return inspect(path.as_deref()).map(|_| Exit::Completed),
+      None => {}
+    }
+
+    let (exit_tx, mut exit_rx) = {
+      let mut pm = self.0.lock().unwrap();
+      let exit_rx = pm.exit_rx.take().ok_or_else(|| Report::msg("The process manager has already been started"))?;
+      (pm.exit_tx.clone(), exit_rx)
+    };
+    let (shutdown_tx, shutdown_rx) = watch::channel(false);
+    if handle_signals {
+      Self::register_hooks(exit_tx.clone())?;
+    }
 
     // If no rpc url provided the we need to start a local sui-node
-    let rpc_client_url = if let Some(rpc_client_url) = rpc_client_url {
-      rpc_client_url.clone()
+    let (rpc_client_url, local_node) = if let Some(rpc_client_url) = config.rpc.url {
+      (rpc_client_url, None)
     } else {
-      tasks.push(self.spawn_sui_node());
-      "http://127.0.0.1:9000".to_string()
+      let config_path = config.node.config_path.as_deref().expect("sui-node config should be provided");
+      let rpc_client_url = read_rpc_url(config_path)?;
+      let local_node = self.spawn_sui_node(rpc_client_url.clone(), exit_tx.clone());
+
+      (rpc_client_url, Some(local_node))
     };
 
-    tasks.push(self.spawn_firehose_streamer(rpc_client_url));
-    self.register_hooks();
+    if let Some(listen_address) = config.http.listen_address {
+      let stall_timeout = Duration::from_secs(config.http.stall_timeout_secs);
+      Self::spawn_http_server(listen_address, stall_timeout, exit_tx.clone());
+      // The network tip is only reported through the health check and the metrics
+      spawn(poll_network_tip(rpc_client_url.clone()));
+    }
+
+    self.spawn_firehose_streamer(rpc_client_url, exit_tx, shutdown_rx, local_node);
+
+    let mut exit = exit_rx.recv().await.unwrap_or(Exit::Failure);
+
+    if exit == Exit::Signal {
+      info!("Finishing the current checkpoint before exiting. Send the signal again to exit immediately");
+      let _ = shutdown_tx.send(true);
+
+      exit = match exit_rx.recv().await {
+        Some(Exit::Completed) => Exit::Signal,
+        _ => Exit::Failure,
+      };
+    }
+
+    self.kill_all().await;
+
+    Ok(exit)
   }
 
-  fn spawn_sui_node (&mut self) -> JoinHandle<()> {
+  /// Returns whether the sui-node is currently ready to serve RPC calls, and a way to restart it
+  fn spawn_sui_node (
+    &mut self,
+    rpc_client_url: String,
+    exit_tx: UnboundedSender<Exit>,
+  ) -> (watch::Receiver<bool>, UnboundedSender<()>) {
     let (tx, rx) = channel();
+    let (ready_tx, ready_rx) = watch::channel(false);
+    let (restart_tx, restart_rx) = unbounded_channel();
     let pm = Arc::clone(&self.0);
     let mut pm = pm.lock().unwrap();
-    let sui_config = pm.args.sui_node_config.clone();
+    let sui_node = SuiNode::new(&pm.config.node, rpc_client_url);
+
+    let handle = spawn(async move {
+      if let Err(e) = sui_node.start(rx, ready_tx, restart_rx).await {
+        error!("{}", e);
+        let _ = exit_tx.send(Exit::Failure);
+      }
+    });
 
-    pm.tasks.push(tx);
+    pm.tasks.push((tx, handle));
 
+    (ready_rx, restart_tx)
+  }
+
+  fn spawn_http_server(listen_address: SocketAddr, stall_timeout: Duration, exit_tx: UnboundedSender<Exit>) {
     spawn(async move {
-      let sui_node = SuiNode::new(sui_config);
-      sui_node.start(rx).await;
-    })
+      if let Err(e) = server::serve(listen_address, stall_timeout).await {
+        error!("{}", e);
+        let _ = exit_tx.send(Exit::Failure);
+      }
+    });
   }
 
-  fn spawn_firehose_streamer(&mut self, rpc_client_url: String) -> JoinHandle<()> {
+  fn spawn_firehose_streamer(
+    &mut self,
+    rpc_client_url: String,
+    exit_tx: UnboundedSender<Exit>,
+    shutdown_rx: watch::Receiver<bool>,
+    local_node: Option<(watch::Receiver<bool>, UnboundedSender<()>)>,
+  ) {
     let pm = Arc::clone(&self.0);
-    let pm = pm.lock().unwrap();
-    let chain_id = pm.args.chain_id.clone();
-    let starting_checkpoint_seq = pm.args.starting_checkpoint_seq.clone();
+    let mut pm = pm.lock().unwrap();
+    let config = pm.config.clone();
+    let processors = mem::take(&mut pm.processors);
 
     spawn(async move {
-      let mut fireshose_streamer = FirehoseStreamer::new(chain_id, rpc_client_url, starting_checkpoint_seq);
-      if let Err(e) = fireshose_streamer.start().await {
-        panic!("{}", e);
+      let mut fireshose_streamer = FirehoseStreamer::new(rpc_client_url, &config).with_processors(processors);
+      if let Some((node_ready, node_restart)) = local_node {
+        fireshose_streamer = fireshose_streamer.with_local_node(node_ready, node_restart);
       }
-    })
+
+      let exit = match fireshose_streamer.start(shutdown_rx).await {
+        Ok(_) => Exit::Completed,
+        Err(e) => {
+          error!("Firehose streamer failed: {}", e);
+          Exit::Failure
+        }
+      };
+
+      let _ = exit_tx.send(exit);
+    });
   }
 
-  pub fn kill_all(&mut self) {
+  async fn kill_all(&mut self) {
     info!("Killing all processes and exiting");
 
-    let pm = Arc::clone(&self.0);
-    let mut pm = pm.lock().unwrap();
-    let tasks = mem::take(&mut pm.tasks);
+    let tasks = {
+      let pm = Arc::clone(&self.0);
+      let mut pm = pm.lock().unwrap();
+      mem::take(&mut pm.tasks)
+    };
 
-    for task in tasks {
-      task.send(()).expect("send task termination messages");
+    for (tx, handle) in tasks {
+      // the task might have already exited i.e. if the sui-node crashed
+      if tx.send(()).is_ok() {
+        let _ = handle.await;
+      }
     }
 
-    process::exit(1);
+    let _ = io::stdout().flush();
+    telemetry::shutdown();
   }
 }
@@ -0,0 +1,235 @@
+use std::any::type_name;
+use backoff::{ExponentialBackoff, backoff::Backoff};
+use eyre::Result;
+use log::warn;
+use tokio::time::sleep;
+use crate::pb::sui::checkpoint as pb;
+
+/// Custom logic run by the streamer on every checkpoint it emits, i.e. aggregations. The callbacks see the
+/// transactions and changed objects left once filtered and projected, in the order they are emitted.
+///
+/// Processors run before the checkpoint is printed, so a checkpoint is only emitted once every processor has
+/// accepted it. They only run once the checkpoint has been fetched, and are never interrupted by a shutdown or the
+/// watchdog, so every checkpoint is processed once and then printed. Only `ErrorPolicy::Retry` calls a callback
+/// again, after it failed.
+pub trait CheckpointProcessor: Send {
+  /// Used in logs and errors
+  fn name(&self) -> &str {
+    type_name::<Self>()
+  }
+
+  fn on_checkpoint_start(&mut self, _checkpoint: &pb::Checkpoint) -> Result<()> {
+    Ok(())
+  }
+
+  fn on_transaction(
+    &mut self,
+    _checkpoint: &pb::Checkpoint,
+    _transaction: &pb::CheckpointTransactionBlockResponse,
+  ) -> Result<()> {
+    Ok(())
+  }
+
+  fn on_changed_object(&mut self, _checkpoint: &pb::Checkpoint, _changed_object: &pb::ChangedObject) -> Result<()> {
+    Ok(())
+  }
+
+  fn on_checkpoint_end(&mut self, _checkpoint: &pb::Checkpoint) -> Result<()> {
+    Ok(())
+  }
+}
+
+/// What happens when a callback of a processor fails
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorPolicy {
+  /// Streaming fails and the process exits with an error
+  Stop,
+  /// The error is logged and the processor moves on to the next callback
+  Skip,
+  /// The callback is called again with the retry settings, and streaming fails once they give up
+  Retry,
+}
+
+struct RegisteredProcessor {
+  processor: Box<dyn CheckpointProcessor>,
+  error_policy: ErrorPolicy,
+}
+
+impl RegisteredProcessor {
+  async fn process(&mut self, checkpoint_data: &pb::CheckpointData, backoff: &ExponentialBackoff) -> Result<()> {
+    let checkpoint = checkpoint_data.checkpoint.as_ref().expect("checkpoint should be converted");
+
+    self.call(backoff, "on_checkpoint_start", |p| p.on_checkpoint_start(checkpoint)).await?;
+
+    for transaction in &checkpoint_data.transactions {
+      self.call(backoff, "on_transaction", |p| p.on_transaction(checkpoint, transaction)).await?;
+    }
+
+    for changed_object in &checkpoint_data.changed_objects {
+      self.call(backoff, "on_changed_object", |p| p.on_changed_object(checkpoint, changed_object)).await?;
+    }
+
+    self.call(backoff, "on_checkpoint_end", |p| p.on_checkpoint_end(checkpoint)).await
+  }
+
+  async fn call<F>(&mut self, backoff: &ExponentialBackoff, callback_name: &str, mut callback: F) -> Result<()>
+  where
+    F: FnMut(&mut dyn CheckpointProcessor) -> Result<()>,
+  {
+    let mut backoff = backoff.clone();
+    backoff.reset();
+
+    loop {
+      let e = match callback(self.processor.as_mut()) {
+        Ok(()) => return Ok(()),
+        Err(e) => e,
+      };
+
+      match (self.error_policy, backoff.next_backoff()) {
+        (ErrorPolicy::Skip, _) => {
+          warn!("[fh-stream] processor {} failed in {}, skipping: {}", self.processor.name(), callback_name, e);
+          return Ok(())
+        }
+        (ErrorPolicy::Retry, Some(delay)) => {
+          warn!(
+            "[fh-stream] processor {} failed in {}, retrying in {:?}: {}",
+            self.processor.name(),
+            callback_name,
+            delay,
+            e,
+          );
+          sleep(delay).await;
+        }
+        (ErrorPolicy::Stop, _) | (ErrorPolicy::Retry, None) => {
+          return Err(e.wrap_err(format!("Processor {} failed in {}", self.processor.name(), callback_name)))
+        }
+      }
+    }
+  }
+}
+
+/// The processors registered with the streamer, called in the order they were registered
+#[derive(Default)]
+pub struct Processors(Vec<RegisteredProcessor>);
+
+impl Processors {
+  pub fn register(&mut self, processor: Box<dyn CheckpointProcessor>, error_policy: ErrorPolicy) {
+    self.0.push(RegisteredProcessor {processor, error_policy});
+  }
+
+  pub(crate) async fn process(
+    &mut self,
+    checkpoint_data: &pb::CheckpointData,
+    backoff: &ExponentialBackoff,
+  ) -> Result<()> {
+    for registered in &mut self.0 {
+      registered.process(checkpoint_data, backoff).await?;
+    }
+
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::{sync::{Arc, Mutex}, time::Duration};
+  use eyre::Report;
+
+  /// Records its calls and fails the first `failures` calls to `on_transaction`
+  struct FlakyProcessor {
+    calls: Arc<Mutex<Vec<String>>>,
+    failures: usize,
+  }
+
+  impl CheckpointProcessor for FlakyProcessor {
+    fn on_checkpoint_start(&mut self, _checkpoint: &pb::Checkpoint) -> Result<()> {
+      self.calls.lock().unwrap().push("start".to_string());
+      Ok(())
+    }
+
+    fn on_transaction(
+      &mut self,
+      _checkpoint: &pb::Checkpoint,
+      transaction: &pb::CheckpointTransactionBlockResponse,
+    ) -> Result<()> {
+      self.calls.lock().unwrap().push(transaction.digest.clone());
+
+      if self.failures > 0 {
+        self.failures -= 1;
+        return Err(Report::msg("flaky"))
+      }
+
+      Ok(())
+    }
+
+    fn on_checkpoint_end(&mut self, _checkpoint: &pb::Checkpoint) -> Result<()> {
+      self.calls.lock().unwrap().push("end".to_string());
+      Ok(())
+    }
+  }
+
+  fn backoff() -> ExponentialBackoff {
+    ExponentialBackoff {
+      initial_interval: Duration::from_millis(1),
+      max_interval: Duration::from_millis(1),
+      max_elapsed_time: Some(Duration::from_millis(200)),
+      ..Default::default()
+    }
+  }
+
+  /// Processes a checkpoint with two transactions and returns the result along with the calls
+  async fn process(failures: usize, error_policy: ErrorPolicy) -> (Result<()>, Vec<String>) {
+    let calls = Arc::new(Mutex::new(vec![]));
+    let mut processors = Processors::default();
+    processors.register(Box::new(FlakyProcessor {calls: calls.clone(), failures}), error_policy);
+
+    let checkpoint_data = pb::CheckpointData {
+      checkpoint: Some(pb::Checkpoint::default()),
+      transactions: ["tx-1", "tx-2"]
+      .iter()
+      .map(|digest| pb::CheckpointTransactionBlockResponse {digest: digest.to_string(), ..Default::default()})
+      .collect(),
+      changed_objects: vec![],
+    };
+
+    let result = processors.process(&checkpoint_data, &backoff()).await;
+    let calls = calls.lock().unwrap().clone();
+
+    (result, calls)
+  }
+
+  #[tokio::test]
+  async fn stop_fails_on_the_first_error() {
+    let (result, calls) = process(1, ErrorPolicy::Stop).await;
+
+    let error = result.unwrap_err();
+    assert!(error.to_string().contains("failed in on_transaction"), "{}", error);
+    assert_eq!(calls, vec!["start", "tx-1"]);
+  }
+
+  #[tokio::test]
+  async fn skip_moves_on_to_the_next_callback() {
+    let (result, calls) = process(1, ErrorPolicy::Skip).await;
+
+    result.unwrap();
+    assert_eq!(calls, vec!["start", "tx-1", "tx-2", "end"]);
+  }
+
+  #[tokio::test]
+  async fn retry_calls_the_failed_callback_again() {
+    let (result, calls) = process(2, ErrorPolicy::Retry).await;
+
+    result.unwrap();
+    assert_eq!(calls, vec!["start", "tx-1", "tx-1", "tx-1", "tx-2", "end"]);
+  }
+
+  #[tokio::test]
+  async fn retry_fails_once_the_retries_give_up() {
+    let (result, calls) = process(usize::MAX, ErrorPolicy::Retry).await;
+
+    assert!(result.is_err());
+    assert!(calls.len() > 2);
+    assert!(calls.iter().skip(1).all(|call| call == "tx-1"));
+  }
+}
@@ -0,0 +1,157 @@
+use std::collections::HashSet;
+use clap::ValueEnum;
+use serde::{Serialize, Deserialize};
+use sui_json_rpc_types::{SuiObjectDataOptions, SuiTransactionBlockResponseOptions};
+use crate::pb::sui::checkpoint as pb;
+
+/// The parts of `CheckpointTransactionBlockResponse` and `SuiObjectData` that can be emitted
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Field {
+  Transaction,
+  RawTransaction,
+  Effects,
+  Events,
+  ObjectType,
+  ObjectOwner,
+  ObjectPreviousTransaction,
+  ObjectStorageRebate,
+  ObjectDisplay,
+  ObjectContent,
+  ObjectBcs,
+}
+
+/// Predefined sets of fields
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum OutputProfile {
+  /// Everything but the parsed object content and display
+  #[default]
+  Lossless,
+  /// Everything
+  Full,
+  /// Raw BCS only i.e. the raw transaction and the object BCS bytes, along with the effects
+  Raw,
+  /// Parsed data only i.e. the transaction, effects, events and the parsed object content
+  Parsed,
+  /// Effects and the object types and owners only
+  Minimal,
+}
+
+impl OutputProfile {
+  fn fields(&self) -> Vec<Field> {
+    use Field::*;
+
+    match self {
+      OutputProfile::Lossless => vec![
+        Transaction, RawTransaction, Effects, Events, ObjectType, ObjectOwner, ObjectPreviousTransaction,
+        ObjectStorageRebate, ObjectBcs,
+      ],
+      OutputProfile::Full => Field::value_variants().to_vec(),
+      OutputProfile::Raw => vec![RawTransaction, Effects, ObjectType, ObjectBcs],
+      OutputProfile::Parsed => vec![
+        Transaction, Effects, Events, ObjectType, ObjectOwner, ObjectPreviousTransaction, ObjectStorageRebate,
+        ObjectDisplay, ObjectContent,
+      ],
+      OutputProfile::Minimal => vec![Effects, ObjectType, ObjectOwner],
+    }
+  }
+}
+
+/// Strips the fields that are not needed from the emitted messages and avoids fetching them in the first place.
+#[derive(Debug, Clone)]
+pub struct Projection {
+  fields: HashSet<Field>,
+}
+
+impl Default for Projection {
+  fn default() -> Self {
+    Self::new(OutputProfile::default(), &[], &[])
+  }
+}
+
+impl Projection {
+  pub fn new(profile: OutputProfile, with_fields: &[Field], without_fields: &[Field]) -> Self {
+    let mut fields = profile.fields().into_iter().collect::<HashSet<_>>();
+    fields.extend(with_fields);
+    fields.retain(|f| !without_fields.contains(f));
+
+    Self {fields}
+  }
+
+  pub fn includes(&self, field: Field) -> bool {
+    self.fields.contains(&field)
+  }
+
+  /// The transaction input, effects and events are always fetched as they are needed to build the checkpoint
+  /// data, to find the changed objects and to compute the checkpoint stats.
+  pub fn transaction_options(&self) -> SuiTransactionBlockResponseOptions {
+    let options = SuiTransactionBlockResponseOptions::new()
+    .with_input()
+    .with_effects()
+    .with_events();
+
+    if self.includes(Field::RawTransaction) {
+      options.with_raw_input()
+    } else {
+      options
+    }
+  }
+
+  /// The object type and previous transaction are always fetched as the filters depend on them.
+  pub fn object_options(&self) -> SuiObjectDataOptions {
+    SuiObjectDataOptions {
+      show_type: true,
+      show_owner: self.includes(Field::ObjectOwner),
+      show_previous_transaction: true,
+      show_display: self.includes(Field::ObjectDisplay),
+      show_content: self.includes(Field::ObjectContent),
+      show_bcs: self.includes(Field::ObjectBcs),
+      show_storage_rebate: self.includes(Field::ObjectStorageRebate),
+    }
+  }
+
+  pub fn project_transaction(&self, source: &mut pb::CheckpointTransactionBlockResponse) {
+    if !self.includes(Field::Transaction) {
+      source.transaction = None;
+    }
+    if !self.includes(Field::RawTransaction) {
+      source.raw_transaction = vec![];
+    }
+    if !self.includes(Field::Effects) {
+      source.effects = None;
+    }
+    if !self.includes(Field::Events) {
+      source.events = None;
+    }
+  }
+
+  pub fn project_object(&self, source: &mut pb::ChangedObject) {
+    let data = match source.data.as_mut() {
+      Some(data) => data,
+      None => return,
+    };
+
+    if !self.includes(Field::ObjectType) {
+      data.r#type = None;
+    }
+    if !self.includes(Field::ObjectOwner) {
+      data.owner = None;
+    }
+    if !self.includes(Field::ObjectPreviousTransaction) {
+      data.previous_transaction = None;
+    }
+    if !self.includes(Field::ObjectStorageRebate) {
+      data.storage_rebate = None;
+    }
+    if !self.includes(Field::ObjectDisplay) {
+      data.display = None;
+    }
+    if !self.includes(Field::ObjectContent) {
+      data.content = None;
+    }
+    if !self.includes(Field::ObjectBcs) {
+      data.bcs = None;
+    }
+  }
+}
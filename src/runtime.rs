@@ -1,98 +1,637 @@
-use std::time::Duration;
+use std::{fs, io::{self, Write}, path::Path, time::{Duration, Instant}};
 use eyre::{Result, Report};
 use jsonrpsee::http_client::{HeaderMap, HeaderValue, HttpClient, HttpClientBuilder};
-use backoff::{ExponentialBackoff, future::retry};
+use backoff::{ExponentialBackoff, future::{retry, retry_notify}};
+use futures::future::pending;
 use prost::Message;
-use log::{error, debug};
+use log::{error, debug, info, warn};
+use sui_indexer::store::CheckpointData;
 use sui_json_rpc::{CLIENT_SDK_TYPE_HEADER};
-use tokio::time::{sleep};
+use sui_json_rpc_types::{Checkpoint, SuiTransactionBlockData, SuiTransactionBlockKind};
+use tokio::{sync::{mpsc::UnboundedSender, watch}, time::{sleep}};
+use tracing::{Span, field::Empty, instrument};
 use crate::{
-  checkpoint_handler::CheckpointHandler, pb::sui::checkpoint as pb,
+  checkpoint_handler::CheckpointHandler, filter::Filter, projection::Projection, config::Config,
+  metrics::METRICS, health::HEALTH, checkpoint_stats::compute_checkpoint_stats, pb::sui::checkpoint as pb,
+  position::CheckpointPosition, processor::{CheckpointProcessor, ErrorPolicy, Processors},
   convert::{
-    tx::convert_transaction, object::convert_object_change, checkpoint::convert_checkpoint,
+    tx::convert_transaction, object::convert_object_change, checkpoint::{convert_checkpoint, convert_epoch_change},
+    sui_system_state::convert_sui_system_state_summary, sui_tx_block_kind::convert_sui_change_epoch,
   },
 };
 
+const NODE_SYNC_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// The first checkpoint of an epoch as observed by the streamer
+struct EpochStart {
+  epoch: u64,
+  checkpoint: u64,
+  timestamp_ms: u64,
+}
+
+/// A checkpoint that has been downloaded and converted, along with what else it needs to be printed
+struct PreparedBlock {
+  checkpoint_data: CheckpointData,
+  checkpoint_data_proto: pb::CheckpointData,
+  change_epoch: Option<pb::SuiChangeEpoch>,
+  system_state: Option<pb::SuiSystemStateSummary>,
+}
+
+/// Converts a downloaded checkpoint, keeping only the transactions and changed objects that pass `filter` and the
+/// fields selected by `projection`
+pub fn convert_checkpoint_data(
+  source: &CheckpointData,
+  filter: &Filter,
+  projection: &Projection,
+) -> pb::CheckpointData {
+  let transactions = source.transactions.iter().map(convert_transaction).collect::<Vec<_>>();
+  let changed_objects = source.changed_objects.iter().map(convert_object_change).collect::<Vec<_>>();
+  let (mut transactions, mut changed_objects) = filter.apply(transactions, changed_objects);
+  transactions.iter_mut().for_each(|tx| projection.project_transaction(tx));
+  changed_objects.iter_mut().for_each(|obj| projection.project_object(obj));
+
+  pb::CheckpointData {
+    checkpoint: Some(convert_checkpoint(&source.checkpoint)),
+    transactions,
+    changed_objects,
+  }
+}
+
 pub struct FirehoseStreamer {
-  rpc_client_url: String,
+  /// The RPC url followed by the fallback ones, the current one being at `rpc_client_url_index`
+  rpc_client_urls: Vec<String>,
+  rpc_client_url_index: usize,
   chain_id: String,
   checkpoint_handler: Option<CheckpointHandler>,
+  filter: Filter,
+  projection: Projection,
+  backoff: ExponentialBackoff,
+  epoch_start: Option<EpochStart>,
+  /// The start and stop positions that still have to be resolved to a sequence number against the RPC node
+  start_position: Option<CheckpointPosition>,
+  stop_position: Option<CheckpointPosition>,
+  stop_checkpoint_seq: Option<u64>,
+  cursor_path: Option<String>,
+  node_ready: Option<watch::Receiver<bool>>,
+  node_ready_timeout: Duration,
+  node_restart: Option<UnboundedSender<()>>,
+  stall_timeout: Option<Duration>,
+  processors: Processors,
+  /// Where the FIRE lines are written, stdout unless embedded or tested
+  output: Box<dyn Write + Send>,
+  /// Consecutive stalls, reset as soon as a checkpoint is emitted
+  stalls: u32,
   pub current_checkpoint_seq: u64,
 }
 
 impl FirehoseStreamer {
-  pub fn new(chain_id: String, rpc_client_url: String, starting_checkpoint_seq: u64) -> Self {
+  pub fn new(rpc_client_url: String, config: &Config) -> Self {
+    let mut rpc_client_urls = vec![rpc_client_url];
+    rpc_client_urls.extend(config.rpc.fallback_urls.iter().cloned());
+
+    let (current_checkpoint_seq, start_position) = match config.starting_checkpoint_seq {
+      CheckpointPosition::Seq(seq) => (seq, None),
+      position => (0, Some(position)),
+    };
+    let (stop_checkpoint_seq, stop_position) = match config.stop_checkpoint_seq {
+      Some(CheckpointPosition::Seq(seq)) => (Some(seq), None),
+      position => (None, position),
+    };
+
     Self {
-      rpc_client_url,
-      chain_id,
-      current_checkpoint_seq: starting_checkpoint_seq,
+      rpc_client_urls,
+      rpc_client_url_index: 0,
+      chain_id: config.chain_id.clone(),
+      current_checkpoint_seq,
+      start_position,
+      stop_position,
+      stop_checkpoint_seq,
+      cursor_path: config.cursor_path.clone(),
       checkpoint_handler: None,
+      filter: config.filter(),
+      projection: config.projection(),
+      backoff: config.retry.backoff(),
+      epoch_start: None,
+      node_ready: None,
+      node_ready_timeout: Duration::from_secs(config.node.ready_timeout_secs),
+      node_restart: None,
+      stall_timeout: Some(config.watchdog.stall_timeout_secs)
+      .filter(|secs| *secs > 0)
+      .map(Duration::from_secs),
+      processors: Processors::default(),
+      output: Box::new(io::stdout()),
+      stalls: 0,
     }
   }
 
-  pub async fn start(&mut self) -> Result<()> {
+  /// Pauses streaming while the local sui-node is down and resumes from the current checkpoint once it is back.
+  /// Streaming only starts once the node has synced up to the starting checkpoint. The node is restarted through
+  /// `node_restart` if streaming stalls repeatedly.
+  pub fn with_local_node(mut self, node_ready: watch::Receiver<bool>, node_restart: UnboundedSender<()>) -> Self {
+    self.node_ready = Some(node_ready);
+    self.node_restart = Some(node_restart);
+    self
+  }
+
+  /// Runs `processor` on every checkpoint before it is emitted, after the processors registered before it
+  pub fn with_processor(mut self, processor: impl CheckpointProcessor + 'static, error_policy: ErrorPolicy) -> Self {
+    self.processors.register(Box::new(processor), error_policy);
+    self
+  }
+
+  pub fn with_processors(mut self, processors: Processors) -> Self {
+    self.processors = processors;
+    self
+  }
+
+  pub fn with_output(mut self, output: impl Write + Send + 'static) -> Self {
+    self.output = Box::new(output);
+    self
+  }
+
+  fn rpc_client_url(&self) -> &str {
+    &self.rpc_client_urls[self.rpc_client_url_index]
+  }
+
+  fn create_checkpoint_handler(&self) -> Result<CheckpointHandler> {
+    let http_client = Self::get_http_client(self.rpc_client_url())?;
+
+    Ok(CheckpointHandler::new(
+      http_client,
+      self.projection.transaction_options(),
+      self.projection.object_options(),
+      self.backoff.clone(),
+    ))
+  }
+
+  /// Creates the RPC client. Must be called before fetching any checkpoint
+  pub async fn connect(&mut self) -> Result<()> {
+    let checkpoint_handler = retry(self.backoff.clone(), || async {
+      Ok(self.create_checkpoint_handler().map_err(|err| {
+        error!("Failed to create HTTP client: {}", err);
+        err
+      })?)
+    }).await?;
+
+    self.checkpoint_handler = Some(checkpoint_handler);
+
+    Ok(())
+  }
+
+  /// Streams checkpoints until the stop checkpoint has been emitted or `true` is sent to `shutdown`. On shutdown,
+  /// a checkpoint that is still being fetched is discarded, while one that is being processed or printed is always
+  /// finished.
+  pub async fn start(&mut self, mut shutdown: watch::Receiver<bool>) -> Result<()> {
+    if let Some(cursor) = self.load_cursor()? {
+      info!("[fh-stream] resuming from checkpoint {} found in the cursor file", cursor);
+      self.current_checkpoint_seq = cursor;
+      self.start_position = None;
+    }
+    METRICS.set_current_checkpoint(self.current_checkpoint_seq);
+    HEALTH.record_checkpoint(self.current_checkpoint_seq);
+
     // Format is FIRE INIT sui-node <PACKAGE_VERSION> <MAJOR_VERSION> <MINOR_VERSION> <CHAIN_ID>
-    println!(
+    writeln!(
+      self.output,
       "\nFIRE INIT sui-node {} sui 0 0 {}",
       env!("CARGO_PKG_VERSION"), self.chain_id,
+    )?;
+
+    self.connect().await?;
+    let mut node_ready = self.node_ready.take();
+    let mut ready = false;
+    let stall_timeout = self.stall_timeout;
+
+    while self.stop_checkpoint_seq.map(|stop| self.current_checkpoint_seq <= stop).unwrap_or(true) {
+      if *shutdown.borrow() {
+        break;
+      }
+
+      if let Some(node_ready) = node_ready.as_mut().filter(|node_ready| !*node_ready.borrow()) {
+        info!("[fh-stream] pausing at checkpoint {} until the sui-node is ready", self.current_checkpoint_seq);
+
+        tokio::select! {
+          Ok(()) = shutdown.changed() => break,
+          _ = Self::wait_for_node_status(node_ready, true) => {
+            info!("[fh-stream] resuming from checkpoint {}", self.current_checkpoint_seq);
+          }
+        }
+      }
+
+      if !ready {
+        let local_node = node_ready.is_some();
+        // A local sui-node may take longer than the stall timeout to sync, which is watched by the ready timeout
+        let ready_stall_timeout = stall_timeout.filter(|_| !local_node);
+
+        tokio::select! {
+          Ok(()) = shutdown.changed() => break,
+          _ = Self::wait_for_node_status_opt(node_ready.as_mut(), false) => continue,
+          _ = Self::stall_timer(ready_stall_timeout) => {
+            self.recover_from_stall(stall_timeout.unwrap_or_default())?;
+            continue;
+          }
+          result = self.wait_until_ready(local_node) => result?,
+        }
+
+        // The stop checkpoint may have just been resolved
+        ready = true;
+        continue;
+      }
+
+      let block = tokio::select! {
+        Ok(()) = shutdown.changed() => {
+          info!("[fh-stream] discarding checkpoint {} that is still being fetched", self.current_checkpoint_seq);
+          break;
+        }
+        _ = Self::wait_for_node_status_opt(node_ready.as_mut(), false) => {
+          info!("[fh-stream] discarding checkpoint {} as the sui-node stopped", self.current_checkpoint_seq);
+          continue;
+        }
+        _ = Self::stall_timer(stall_timeout) => {
+          self.recover_from_stall(stall_timeout.unwrap_or_default())?;
+          continue;
+        }
+        result = self.prepare_next_block() => result?,
+      };
+
+      if self.passed_stop(&block.checkpoint_data.checkpoint) {
+        break;
+      }
+
+      // Emitted outside of the select, so that processors are never interrupted and a processed checkpoint is
+      // always printed, even if shutdown was requested in the meantime
+      self.emit_block(block).await?;
+    }
+
+    self.output.flush()?;
+    self.save_cursor()?;
+
+    Ok(())
+  }
+
+  /// Resolves once no checkpoint has been emitted for `stall_timeout`, never if the watchdog is disabled
+  async fn stall_timer(stall_timeout: Option<Duration>) {
+    match stall_timeout {
+      Some(stall_timeout) => sleep(stall_timeout).await,
+      None => pending::<()>().await,
+    }
+  }
+
+  /// Called by the watchdog, once the checkpoint being fetched has been discarded. The first stall rebuilds the
+  /// RPC client, switching to the next RPC url if there are fallback ones, and the following ones restart the local
+  /// sui-node if there is one.
+  fn recover_from_stall(&mut self, stall_timeout: Duration) -> Result<()> {
+    self.stalls += 1;
+    error!(
+      "[fh-stream] no progress for {:?}, stall {}: {}",
+      stall_timeout,
+      self.stalls,
+      HEALTH.diagnostics(),
     );
 
-    let checkpoint_handler = retry(ExponentialBackoff::default(), || async {
-      let http_client = Self::get_http_client(&self.rpc_client_url).map_err(|err| {
-        error!("Failed to create HTTP client: {}", err);
-        err
-      })?;
+    if self.stalls > 1 {
+      if let Some(node_restart) = &self.node_restart {
+        warn!("[fh-stream] restarting the sui-node");
+        let _ = node_restart.send(());
 
-      Ok(CheckpointHandler::new(http_client))
-    }).await?;
+        return Ok(())
+      }
+    }
 
-    self.checkpoint_handler = Some(checkpoint_handler);
+    self.rpc_client_url_index = (self.rpc_client_url_index + 1) % self.rpc_client_urls.len();
+    warn!("[fh-stream] rebuilding the RPC client for {}", self.rpc_client_url());
+    self.checkpoint_handler = Some(self.create_checkpoint_handler()?);
 
-    loop {
-      self.convert_next_block().await?;
+    Ok(())
+  }
+
+  /// Checks that the RPC node serves the expected chain, after resolving the start and stop positions. A local
+  /// sui-node is first waited for until it has synced past the start position and up to the current checkpoint.
+  async fn wait_until_ready(&mut self, local_node: bool) -> Result<()> {
+    if local_node {
+      self.wait_for_node_sync().await?;
+    } else {
+      self.resolve_start(false).await?;
     }
+    self.resolve_stop().await?;
+
+    let checkpoint_handler = self.checkpoint_handler.as_ref().expect("Checkpoint handler should be created");
+    let genesis_digest = checkpoint_handler.get_checkpoint(0).await?.digest.base58_encode();
+
+    if genesis_digest != self.chain_id {
+      return Err(Report::msg(format!(
+        "The RPC node serves chain {} while chain {} is expected",
+        genesis_digest,
+        self.chain_id,
+      )))
+    }
+
+    info!("[fh-stream] RPC node serves the expected chain {}", self.chain_id);
+    HEALTH.set_chain_id_checked();
+
+    Ok(())
   }
 
-  pub async fn convert_next_block(&mut self) -> Result<()> {
+  /// Resolves the start position if it is not a sequence number i.e. a timestamp or an epoch. With `wait`, returns
+  /// false if the RPC node has not reached it yet instead of failing.
+  async fn resolve_start(&mut self, wait: bool) -> Result<bool> {
     let checkpoint_handler = self.checkpoint_handler.as_ref().expect("Checkpoint handler should be created");
-    let checkpoint_data = retry(ExponentialBackoff::default(), || async {
-      Ok(checkpoint_handler.download_checkpoint_data(self.current_checkpoint_seq).await?)
-    }).await?;
 
-    println!("\nFIRE BLOCK_START {}", self.current_checkpoint_seq);
+    // Only cleared once resolved, as this is cancelled if the sui-node stops in the meantime
+    if let Some(start_position) = self.start_position {
+      let start = match wait {
+        true => start_position.try_resolve_start(checkpoint_handler).await?,
+        false => Some(start_position.resolve_start(checkpoint_handler).await?),
+      };
+      self.current_checkpoint_seq = match start {
+        Some(start) => start,
+        None => return Ok(false),
+      };
+      self.start_position = None;
+      info!("[fh-stream] starting at checkpoint {} resolved from {}", self.current_checkpoint_seq, start_position);
 
-    if checkpoint_data.transactions.is_empty() {
-      debug!("[fh-stream] no transactions to send");
-      sleep(Duration::from_millis(100)).await;
+      METRICS.set_current_checkpoint(self.current_checkpoint_seq);
+      HEALTH.record_checkpoint(self.current_checkpoint_seq);
+    }
+
+    Ok(true)
+  }
 
-      return Ok(())
+  /// Resolves the stop position if the RPC node has already reached it. Otherwise it is kept, and streaming stops
+  /// on the first checkpoint past it, so that a stop in the future does not hold back streaming.
+  async fn resolve_stop(&mut self) -> Result<()> {
+    let checkpoint_handler = self.checkpoint_handler.as_ref().expect("Checkpoint handler should be created");
+
+    if let Some(stop_position) = self.stop_position {
+      match stop_position.try_resolve_stop(checkpoint_handler).await? {
+        Some(stop_checkpoint_seq) => {
+          info!("[fh-stream] stopping at checkpoint {} resolved from {}", stop_checkpoint_seq, stop_position);
+
+          self.stop_checkpoint_seq = Some(stop_checkpoint_seq);
+          self.stop_position = None;
+        }
+        None => info!("[fh-stream] the RPC node has not reached {} yet, stopping once it is passed", stop_position),
+      }
     }
 
+    Ok(())
+  }
+
+  /// Whether `checkpoint` comes after a stop position that was not reached when streaming started, in which case
+  /// the stop is now known to be the previous checkpoint
+  fn passed_stop(&mut self, checkpoint: &Checkpoint) -> bool {
+    let stop_position = match self.stop_position {
+      Some(stop_position) if stop_position.is_past_stop(checkpoint.timestamp_ms, checkpoint.epoch) => stop_position,
+      _ => return false,
+    };
+
+    self.stop_position = None;
+    self.stop_checkpoint_seq = self.current_checkpoint_seq.checked_sub(1);
+    info!(
+      "[fh-stream] checkpoint {} is past {}, stopping after the previous one",
+      self.current_checkpoint_seq,
+      stop_position,
+    );
+
+    true
+  }
+
+  /// Waits until the local sui-node has synced past the start position, which is resolved along the way, and up to
+  /// the current checkpoint. Fails if it stops making progress for longer than the ready timeout.
+  async fn wait_for_node_sync(&mut self) -> Result<()> {
+    let mut last_progress = Instant::now();
+    let mut last_seq = None;
+
+    loop {
+      let checkpoint_handler = self.checkpoint_handler.as_ref().expect("Checkpoint handler should be created");
+      let latest_seq = checkpoint_handler.get_latest_checkpoint_sequence_number().await?;
+
+      if self.resolve_start(true).await? && latest_seq >= self.current_checkpoint_seq {
+        info!("[fh-stream] sui-node has synced up to checkpoint {}", latest_seq);
+        return Ok(())
+      }
+
+      let target = match self.start_position {
+        Some(start_position) => start_position.to_string(),
+        None => format!("checkpoint {}", self.current_checkpoint_seq),
+      };
+
+      if last_seq != Some(latest_seq) {
+        last_seq = Some(latest_seq);
+        last_progress = Instant::now();
+      } else if last_progress.elapsed() > self.node_ready_timeout {
+        return Err(Report::msg(format!(
+          "sui-node has been stuck at checkpoint {} for {:?} while syncing up to {}",
+          latest_seq,
+          self.node_ready_timeout,
+          target,
+        )))
+      }
+
+      info!("[fh-stream] waiting for sui-node to sync up to {}, currently at checkpoint {}", target, latest_seq);
+      sleep(NODE_SYNC_POLL_INTERVAL).await;
+    }
+  }
+
+  /// Resolves once the sui-node readiness is `ready`. Never resolves if the status can no longer change.
+  async fn wait_for_node_status(node_ready: &mut watch::Receiver<bool>, ready: bool) {
+    while *node_ready.borrow() != ready {
+      if node_ready.changed().await.is_err() {
+        pending::<()>().await;
+      }
+    }
+  }
+
+  async fn wait_for_node_status_opt(node_ready: Option<&mut watch::Receiver<bool>>, ready: bool) {
+    match node_ready {
+      Some(node_ready) => Self::wait_for_node_status(node_ready, ready).await,
+      None => pending::<()>().await,
+    }
+  }
+
+  /// The cursor file holds the sequence number of the next checkpoint to stream
+  fn load_cursor(&self) -> Result<Option<u64>> {
+    let cursor_path = match &self.cursor_path {
+      Some(cursor_path) if Path::new(cursor_path).exists() => cursor_path,
+      _ => return Ok(None),
+    };
+
+    let cursor = fs::read_to_string(cursor_path)?.trim().parse::<u64>().map_err(|e| {
+      Report::msg(format!("Invalid cursor file {}: {}", cursor_path, e))
+    })?;
+
+    Ok(Some(cursor))
+  }
+
+  fn save_cursor(&self) -> Result<()> {
+    let cursor_path = match &self.cursor_path {
+      Some(cursor_path) => cursor_path,
+      None => return Ok(()),
+    };
+
+    // Write to a temporary file first so that the cursor is never left half written
+    let tmp_path = format!("{}.tmp", cursor_path);
+    fs::write(&tmp_path, self.current_checkpoint_seq.to_string())?;
+    fs::rename(&tmp_path, cursor_path)?;
+
+    info!("[fh-stream] saved cursor at checkpoint {}", self.current_checkpoint_seq);
+
+    Ok(())
+  }
+
+  /// Downloads, processes and prints the current checkpoint
+  pub async fn convert_next_block(&mut self) -> Result<()> {
+    let block = self.prepare_next_block().await?;
+    self.emit_block(block).await
+  }
+
+  /// Downloads and converts the current checkpoint, along with everything else it needs, so that it can be
+  /// discarded at any await point
+  #[instrument(
+    level = "debug",
+    name = "checkpoint",
+    skip_all,
+    fields(seq = self.current_checkpoint_seq, transactions = Empty, objects = Empty),
+  )]
+  async fn prepare_next_block(&mut self) -> Result<PreparedBlock> {
+    let checkpoint_data = self.download_checkpoint_data().await?;
+
+    let span = Span::current();
+    span.record("transactions", checkpoint_data.transactions.len());
+    span.record("objects", checkpoint_data.changed_objects.len());
+
+    // Looked up before filtering, as the epoch change is emitted even if its transaction is filtered out
+    let change_epoch = Self::find_change_epoch(&checkpoint_data);
+
+    // Everything that needs to be fetched must be fetched before the block starts being printed
+    let system_state = match &change_epoch {
+      Some(_) => Some(self.get_sui_system_state(&checkpoint_data).await?),
+      None => None,
+    };
+
+    let checkpoint_data_proto = convert_checkpoint_data(&checkpoint_data, &self.filter, &self.projection);
+
+    Ok(PreparedBlock {
+      checkpoint_data,
+      checkpoint_data_proto,
+      change_epoch,
+      system_state,
+    })
+  }
+
+  /// Runs the processors on a prepared checkpoint and prints it. Processors may wait between retries, so they run
+  /// before the block starts, as there must be no await point between BLOCK_START and BLOCK_END.
+  async fn emit_block(&mut self, block: PreparedBlock) -> Result<()> {
+    let PreparedBlock {checkpoint_data, checkpoint_data_proto, change_epoch, system_state} = block;
+    self.processors.process(&checkpoint_data_proto, &self.backoff).await?;
+
+    writeln!(self.output, "\nFIRE BLOCK_START {}", self.current_checkpoint_seq)?;
+
     debug!(
       "[fh-stream] got {} transactions from  {}",
       checkpoint_data.transactions.len(),
       self.current_checkpoint_seq,
     );
 
-    Self::print_checkpoint_overview(&convert_checkpoint(&checkpoint_data.checkpoint));
+    let pb::CheckpointData {checkpoint: checkpoint_proto, transactions, changed_objects} = checkpoint_data_proto;
+    self.print_checkpoint_overview(&checkpoint_proto.expect("checkpoint should be converted"))?;
+    self.print_checkpoint_stats(&compute_checkpoint_stats(&checkpoint_data))?;
+
+    METRICS.transactions_emitted.inc_by(transactions.len() as u64);
+    METRICS.objects_emitted.inc_by(changed_objects.len() as u64);
+
+    for txn_proto in &transactions {
+      self.print_transaction(txn_proto)?;
+    }
 
-    for tx in &checkpoint_data.transactions {
-      let txn_proto = convert_transaction(&tx);
-      Self::print_transaction(&txn_proto);
+    for obj_change_proto in &changed_objects {
+      self.print_changed_object(obj_change_proto)?;
     }
 
-    for obj_change in &checkpoint_data.changed_objects {
-      let obj_change_proto = convert_object_change(&obj_change);
-      Self::print_changed_object(&obj_change_proto);
+    let checkpoint = &checkpoint_data.checkpoint;
+    if checkpoint.sequence_number == 0 {
+      self.epoch_start = Some(EpochStart {
+        epoch: checkpoint.epoch,
+        checkpoint: checkpoint.sequence_number,
+        timestamp_ms: checkpoint.timestamp_ms,
+      });
     }
 
-    println!("\nFIRE BLOCK_END {}", self.current_checkpoint_seq);
+    let epoch_start = self.epoch_start
+    .as_ref()
+    .filter(|epoch_start| epoch_start.epoch == checkpoint.epoch)
+    .map(|epoch_start| (epoch_start.checkpoint, epoch_start.timestamp_ms));
+
+    if let Some(epoch_change) = convert_epoch_change(checkpoint, change_epoch.as_ref(), epoch_start) {
+      self.print_epoch_change(&epoch_change)?;
+
+      self.epoch_start = Some(EpochStart {
+        epoch: checkpoint.epoch + 1,
+        checkpoint: checkpoint.sequence_number + 1,
+        timestamp_ms: change_epoch.as_ref().map(|c| c.epoch_start_timestamp_ms).unwrap_or(checkpoint.timestamp_ms),
+      });
+    }
+
+    if let Some(system_state) = &system_state {
+      self.print_system_state(system_state)?;
+    }
+
+    writeln!(self.output, "\nFIRE BLOCK_END {}", self.current_checkpoint_seq)?;
     self.current_checkpoint_seq += 1;
+    self.stalls = 0;
+    METRICS.checkpoints_emitted.inc();
+    METRICS.set_current_checkpoint(self.current_checkpoint_seq);
+    HEALTH.record_checkpoint(self.current_checkpoint_seq);
 
     Ok(())
   }
 
-  fn get_http_client(rpc_client_url: &str) -> Result<HttpClient> {
+  async fn download_checkpoint_data(&self) -> Result<CheckpointData> {
+    let checkpoint_handler = self.checkpoint_handler.as_ref().expect("Checkpoint handler should be created");
+
+    retry_notify(self.backoff.clone(), || async {
+      Ok(checkpoint_handler.download_checkpoint_data(self.current_checkpoint_seq).await?)
+    }, METRICS.retry_notifier("download_checkpoint_data")).await
+  }
+
+  /// Downloads the current checkpoint and converts it the way it would be streamed i.e. filtered and projected
+  pub async fn fetch_checkpoint_data(&self) -> Result<pb::CheckpointData> {
+    let checkpoint_data = self.download_checkpoint_data().await?;
+
+    Ok(convert_checkpoint_data(&checkpoint_data, &self.filter, &self.projection))
+  }
+
+  fn find_change_epoch(checkpoint_data: &CheckpointData) -> Option<pb::SuiChangeEpoch> {
+    checkpoint_data.transactions.iter().find_map(|tx| {
+      let SuiTransactionBlockData::V1(data) = &tx.transaction.data;
+
+      match &data.transaction {
+        SuiTransactionBlockKind::ChangeEpoch(change_epoch) => Some(convert_sui_change_epoch(change_epoch)),
+        _ => None,
+      }
+    })
+  }
+
+  /// Returns the system state at the start of the epoch begun by the `ChangeEpoch` transaction of the checkpoint.
+  /// It is read at the version left by that transaction rather than served as the latest one, so that the snapshot
+  /// is the same whether the checkpoint is streamed live or backfilled.
+  async fn get_sui_system_state(&self, checkpoint_data: &CheckpointData) -> Result<pb::SuiSystemStateSummary> {
+    let checkpoint_handler = self.checkpoint_handler.as_ref().expect("Checkpoint handler should be created");
+    let change_epoch_tx = checkpoint_data
+    .transactions
+    .iter()
+    .find(|tx| {
+      let SuiTransactionBlockData::V1(data) = &tx.transaction.data;
+      matches!(data.transaction, SuiTransactionBlockKind::ChangeEpoch(_))
+    })
+    .expect("the checkpoint should have a ChangeEpoch transaction");
+
+    let system_state = checkpoint_handler.get_sui_system_state_at(&change_epoch_tx.effects).await?;
+
+    Ok(convert_sui_system_state_summary(&system_state))
+  }
+
+  pub fn get_http_client(rpc_client_url: &str) -> Result<HttpClient> {
     let mut headers = HeaderMap::new();
     headers.insert(CLIENT_SDK_TYPE_HEADER, HeaderValue::from_static("indexer"));
   
@@ -106,7 +645,7 @@ impl FirehoseStreamer {
     })
   }
 
-  fn print_checkpoint_overview(checkpoint: &pb::Checkpoint) {
+  fn print_checkpoint_overview(&mut self, checkpoint: &pb::Checkpoint) -> io::Result<()> {
     let mut buf = vec![];
     checkpoint.encode(&mut buf).unwrap_or_else(|_| {
       panic!(
@@ -114,10 +653,47 @@ impl FirehoseStreamer {
         checkpoint
       )
     });
-    println!("\nFIRE CHECKPOINT {}", base64::encode(buf));
+    METRICS.emitted_bytes.with_label_values(&["CHECKPOINT"]).inc_by(buf.len() as u64);
+    writeln!(self.output, "\nFIRE CHECKPOINT {}", base64::encode(buf))
+  }
+
+  fn print_checkpoint_stats(&mut self, stats: &pb::CheckpointStats) -> io::Result<()> {
+    let mut buf = vec![];
+    stats.encode(&mut buf).unwrap_or_else(|_| {
+      panic!(
+        "Could not convert protobuf checkpoint stats to bytes '{:?}'",
+        stats
+      )
+    });
+    METRICS.emitted_bytes.with_label_values(&["CHECKPOINT_STATS"]).inc_by(buf.len() as u64);
+    writeln!(self.output, "\nFIRE CHECKPOINT_STATS {}", base64::encode(buf))
+  }
+
+  fn print_epoch_change(&mut self, epoch_change: &pb::EpochChange) -> io::Result<()> {
+    let mut buf = vec![];
+    epoch_change.encode(&mut buf).unwrap_or_else(|_| {
+      panic!(
+        "Could not convert protobuf epoch change to bytes '{:?}'",
+        epoch_change
+      )
+    });
+    METRICS.emitted_bytes.with_label_values(&["EPOCH"]).inc_by(buf.len() as u64);
+    writeln!(self.output, "\nFIRE EPOCH {}", base64::encode(buf))
+  }
+
+  fn print_system_state(&mut self, system_state: &pb::SuiSystemStateSummary) -> io::Result<()> {
+    let mut buf = vec![];
+    system_state.encode(&mut buf).unwrap_or_else(|_| {
+      panic!(
+        "Could not convert protobuf system state to bytes '{:?}'",
+        system_state
+      )
+    });
+    METRICS.emitted_bytes.with_label_values(&["SYSTEM_STATE"]).inc_by(buf.len() as u64);
+    writeln!(self.output, "\nFIRE SYSTEM_STATE {}", base64::encode(buf))
   }
 
-  fn print_transaction(transaction: &pb::CheckpointTransactionBlockResponse) {
+  fn print_transaction(&mut self, transaction: &pb::CheckpointTransactionBlockResponse) -> io::Result<()> {
     let mut buf = vec![];
     transaction.encode(&mut buf).unwrap_or_else(|_| {
       panic!(
@@ -125,10 +701,11 @@ impl FirehoseStreamer {
         transaction
       )
     });
-    println!("\nFIRE TRX {}", base64::encode(buf));
+    METRICS.emitted_bytes.with_label_values(&["TRX"]).inc_by(buf.len() as u64);
+    writeln!(self.output, "\nFIRE TRX {}", base64::encode(buf))
   }
 
-  fn print_changed_object(obj_change: &pb::ChangedObject) {
+  fn print_changed_object(&mut self, obj_change: &pb::ChangedObject) -> io::Result<()> {
     let mut buf = vec![];
     obj_change.encode(&mut buf).unwrap_or_else(|_| {
       panic!(
@@ -136,6 +713,7 @@ impl FirehoseStreamer {
         obj_change
       )
     });
-    println!("\nFIRE OBJ {}", base64::encode(buf));
+    METRICS.emitted_bytes.with_label_values(&["OBJ"]).inc_by(buf.len() as u64);
+    writeln!(self.output, "\nFIRE OBJ {}", base64::encode(buf))
   }
 }
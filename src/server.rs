@@ -0,0 +1,47 @@
+use std::{convert::Infallible, net::SocketAddr, time::Duration};
+use eyre::{Result, Report};
+use hyper::{
+  Body, Request, Response, Server, StatusCode, header::CONTENT_TYPE, service::{make_service_fn, service_fn},
+};
+use log::info;
+use crate::{metrics::METRICS, health::HEALTH};
+
+/// Serves the Prometheus metrics on `/metrics`, and the liveness and readiness probes on `/healthz` and `/readyz`.
+/// The liveness probe fails once no checkpoint has been emitted for `stall_timeout` while the network moves on.
+pub async fn serve(listen_address: SocketAddr, stall_timeout: Duration) -> Result<()> {
+  let make_service = make_service_fn(move |_| async move {
+    Ok::<_, Infallible>(service_fn(move |request| handle(request, stall_timeout)))
+  });
+
+  let server = Server::try_bind(&listen_address)
+  .map_err(|e| Report::msg(format!("Failed to bind the HTTP server to {}: {}", listen_address, e)))?
+  .serve(make_service);
+
+  info!("Serving metrics and health checks on http://{}", listen_address);
+
+  server.await.map_err(|e| Report::msg(format!("HTTP server failed: {}", e)))
+}
+
+async fn handle(request: Request<Body>, stall_timeout: Duration) -> Result<Response<Body>, Infallible> {
+  let response = match request.uri().path() {
+    "/metrics" => Response::builder()
+    .header(CONTENT_TYPE, "text/plain; version=0.0.4")
+    .body(Body::from(METRICS.encode())),
+    "/healthz" => status_response(HEALTH.check_healthy(stall_timeout)),
+    "/readyz" => status_response(HEALTH.check_ready()),
+    _ => Response::builder()
+    .status(StatusCode::NOT_FOUND)
+    .body(Body::empty()),
+  };
+
+  Ok(response.expect("valid response"))
+}
+
+fn status_response(status: Result<(), String>) -> Result<Response<Body>, hyper::http::Error> {
+  match status {
+    Ok(()) => Response::builder().body(Body::from("ok")),
+    Err(reason) => Response::builder()
+    .status(StatusCode::SERVICE_UNAVAILABLE)
+    .body(Body::from(reason)),
+  }
+}
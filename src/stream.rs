@@ -0,0 +1,301 @@
+use std::{cmp, future::Future, iter, time::Duration};
+use backoff::{ExponentialBackoff, future::retry_notify};
+use eyre::{Result, Report};
+use futures::{Stream, StreamExt, future::join_all, stream};
+use log::{info, warn};
+use tokio::time::sleep;
+use crate::{
+  checkpoint_handler::CheckpointHandler, config::Config, position::CheckpointPosition, pb::sui::checkpoint as pb,
+  runtime::{FirehoseStreamer, convert_checkpoint_data}, metrics::METRICS,
+};
+
+pub use sui_indexer::store::CheckpointData;
+
+const TIP_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Builds a stream of checkpoints for embedding the indexer in another service, instead of printing FIRE lines to
+/// stdout.
+///
+/// ```ignore
+/// let mut checkpoints = Box::pin(
+///   CheckpointStreamBuilder::new("https://fullnode.mainnet.sui.io:443")
+///   .with_start(CheckpointPosition::Epoch(120))
+///   .with_concurrency(8)
+///   .build()?
+/// );
+///
+/// while let Some(checkpoint_data) = checkpoints.next().await {
+///   let checkpoint_data = checkpoint_data?;
+/// }
+/// ```
+///
+/// Checkpoints are yielded in order. Up to `concurrency` of them are downloaded at the same time, but never past
+/// the latest checkpoint of the RPC node, which is polled once the stream has caught up with it. A failed download
+/// or position lookup is retried with the retry settings on each endpoint in turn, and the stream ends with an error
+/// once all of them gave up. A stop position the RPC node has not reached yet ends the stream on the first checkpoint
+/// past it. The cursor file and the local sui-node settings of the config are not used.
+pub struct CheckpointStreamBuilder {
+  config: Config,
+  concurrency: usize,
+}
+
+impl CheckpointStreamBuilder {
+  pub fn new(rpc_client_url: impl Into<String>) -> Self {
+    let mut config = Config::default();
+    config.rpc.url = Some(rpc_client_url.into());
+
+    Self::from_config(config)
+  }
+
+  /// Uses the endpoints, positions, filter, output profile and retry settings of `config`
+  pub fn from_config(config: Config) -> Self {
+    Self {
+      config,
+      concurrency: 1,
+    }
+  }
+
+  pub fn with_start(mut self, start: CheckpointPosition) -> Self {
+    self.config.starting_checkpoint_seq = start;
+    self
+  }
+
+  /// The last checkpoint to yield, inclusive. The stream never ends if not set
+  pub fn with_stop(mut self, stop: CheckpointPosition) -> Self {
+    self.config.stop_checkpoint_seq = Some(stop);
+    self
+  }
+
+  /// An endpoint to fall back to when a download fails on the previous ones. Can be called several times
+  pub fn with_fallback_url(mut self, rpc_client_url: impl Into<String>) -> Self {
+    self.config.rpc.fallback_urls.push(rpc_client_url.into());
+    self
+  }
+
+  /// The maximum number of checkpoints downloaded at the same time
+  pub fn with_concurrency(mut self, concurrency: usize) -> Self {
+    self.concurrency = concurrency.max(1);
+    self
+  }
+
+  /// Builds a stream of converted checkpoints, filtered and projected the same way as the streamed ones
+  pub fn build(self) -> Result<impl Stream<Item = Result<pb::CheckpointData>>> {
+    let filter = self.config.filter();
+    let projection = self.config.projection();
+
+    Ok(self.build_raw()?.map(move |checkpoint_data| {
+      checkpoint_data.map(|checkpoint_data| convert_checkpoint_data(&checkpoint_data, &filter, &projection))
+    }))
+  }
+
+  /// Builds a stream of checkpoints as downloaded from the RPC node. Only the fields selected by the output profile
+  /// are fetched.
+  pub fn build_raw(self) -> Result<impl Stream<Item = Result<CheckpointData>>> {
+    let rpc_client_url = self.config.rpc.url.as_ref().ok_or_else(|| {
+      Report::msg("An rpc client url must be provided")
+    })?;
+    let projection = self.config.projection();
+
+    let checkpoint_handlers = iter::once(rpc_client_url)
+    .chain(self.config.rpc.fallback_urls.iter())
+    .map(|url| {
+      Ok(CheckpointHandler::new(
+        FirehoseStreamer::get_http_client(url)?,
+        projection.transaction_options(),
+        projection.object_options(),
+        self.config.retry.backoff(),
+      ))
+    })
+    .collect::<Result<Vec<_>>>()?;
+
+    let state = StreamState {
+      checkpoint_handlers,
+      start: Some(self.config.starting_checkpoint_seq),
+      stop: self.config.stop_checkpoint_seq,
+      positions_resolved: false,
+      next_seq: 0,
+      stop_seq: None,
+      tip: None,
+      concurrency: self.concurrency,
+      backoff: self.config.retry.backoff(),
+      done: false,
+    };
+
+    Ok(stream::unfold(state, StreamState::next_batch).flat_map(stream::iter))
+  }
+}
+
+struct StreamState {
+  checkpoint_handlers: Vec<CheckpointHandler>,
+  /// The positions that have not been resolved yet, which happens on the first poll. A stop the RPC node has not
+  /// reached by then is kept, and detected on the first checkpoint past it.
+  start: Option<CheckpointPosition>,
+  stop: Option<CheckpointPosition>,
+  positions_resolved: bool,
+  next_seq: u64,
+  stop_seq: Option<u64>,
+  tip: Option<u64>,
+  concurrency: usize,
+  backoff: ExponentialBackoff,
+  done: bool,
+}
+
+impl StreamState {
+  /// Downloads the next checkpoints, as many as the concurrency and the network tip allow
+  async fn next_batch(mut self) -> Option<(Vec<Result<CheckpointData>>, Self)> {
+    if self.done || self.stop_seq.map(|stop_seq| self.next_seq > stop_seq).unwrap_or(false) {
+      return None
+    }
+
+    let last_seq = match self.prepare_batch().await {
+      Ok(last_seq) => last_seq,
+      Err(e) => {
+        self.done = true;
+        return Some((vec![Err(e)], self))
+      }
+    };
+
+    let mut batch = join_all((self.next_seq..=last_seq).map(|seq| {
+      download_checkpoint_data(&self.checkpoint_handlers, &self.backoff, seq)
+    }))
+    .await;
+
+    if let Some(index) = batch.iter().position(|result| result.is_err()) {
+      batch.truncate(index + 1);
+      self.done = true;
+    }
+
+    if let Some(stop) = self.stop {
+      let past_stop = batch.iter().position(|result| match result {
+        Ok(CheckpointData {checkpoint, ..}) => stop.is_past_stop(checkpoint.timestamp_ms, checkpoint.epoch),
+        Err(_) => false,
+      });
+
+      if let Some(index) = past_stop {
+        let seq = self.next_seq + index as u64;
+        info!("[fh-stream] checkpoint {} is past {}, stopping after the previous one", seq, stop);
+        batch.truncate(index);
+        self.done = true;
+      }
+    }
+
+    self.next_seq = last_seq + 1;
+
+    Some((batch, self))
+  }
+
+  /// Resolves the positions if needed and waits until the next checkpoint exists. Returns the last checkpoint of
+  /// the batch.
+  async fn prepare_batch(&mut self) -> Result<u64> {
+    if !self.positions_resolved {
+      self.resolve_positions().await?;
+      self.positions_resolved = true;
+    }
+
+    loop {
+      if let Some(tip) = self.tip.filter(|tip| *tip >= self.next_seq) {
+        let mut last_seq = cmp::min(tip, self.next_seq + self.concurrency as u64 - 1);
+        if let Some(stop_seq) = self.stop_seq {
+          last_seq = cmp::min(last_seq, stop_seq);
+        }
+
+        return Ok(last_seq)
+      }
+
+      if self.tip.is_some() {
+        sleep(TIP_POLL_INTERVAL).await;
+      }
+
+      self.tip = Some(get_latest_checkpoint_sequence_number(&self.checkpoint_handlers).await?);
+    }
+  }
+
+  /// Each position is only cleared once resolved, so that a failed lookup does not lose it
+  async fn resolve_positions(&mut self) -> Result<()> {
+    if let Some(start) = self.start {
+      self.next_seq = with_fallback(&self.checkpoint_handlers, &self.backoff, "resolve_start", |checkpoint_handler| {
+        start.resolve_start(checkpoint_handler)
+      })
+      .await?;
+      self.start = None;
+      info!("[fh-stream] starting at checkpoint {} resolved from {}", self.next_seq, start);
+    }
+
+    if let Some(stop) = self.stop {
+      let stop_seq = with_fallback(&self.checkpoint_handlers, &self.backoff, "resolve_stop", |checkpoint_handler| {
+        stop.try_resolve_stop(checkpoint_handler)
+      })
+      .await?;
+
+      match stop_seq {
+        Some(stop_seq) => {
+          self.stop_seq = Some(stop_seq);
+          self.stop = None;
+          info!("[fh-stream] stopping at checkpoint {} resolved from {}", stop_seq, stop);
+        }
+        None => info!("[fh-stream] the RPC node has not reached {} yet, stopping once it is passed", stop),
+      }
+    }
+
+    Ok(())
+  }
+}
+
+async fn download_checkpoint_data(
+  checkpoint_handlers: &[CheckpointHandler],
+  backoff: &ExponentialBackoff,
+  seq: u64,
+) -> Result<CheckpointData> {
+  with_fallback(checkpoint_handlers, backoff, "download_checkpoint_data", |checkpoint_handler| async move {
+    checkpoint_handler.download_checkpoint_data(seq).await.map_err(|e| {
+      Report::msg(format!("Failed to download checkpoint {}: {}", seq, e))
+    })
+  })
+  .await
+}
+
+/// Tries every endpoint in order, retrying `operation` on each one with `backoff` before moving on to the next
+async fn with_fallback<'a, T, F, Fut>(
+  checkpoint_handlers: &'a [CheckpointHandler],
+  backoff: &ExponentialBackoff,
+  operation: &'static str,
+  f: F,
+) -> Result<T>
+where
+  F: Fn(&'a CheckpointHandler) -> Fut,
+  Fut: Future<Output = Result<T>>,
+{
+  let mut last_error = None;
+
+  for checkpoint_handler in checkpoint_handlers {
+    let result = retry_notify(backoff.clone(), || async {
+      Ok(f(checkpoint_handler).await?)
+    }, METRICS.retry_notifier(operation)).await;
+
+    match result {
+      Ok(value) => return Ok(value),
+      Err(e) => {
+        warn!("[fh-stream] {} failed on an endpoint: {}", operation, e);
+        last_error = Some(e);
+      }
+    }
+  }
+
+  Err(last_error.expect("at least one endpoint"))
+}
+
+async fn get_latest_checkpoint_sequence_number(checkpoint_handlers: &[CheckpointHandler]) -> Result<u64> {
+  let mut last_error = None;
+
+  for checkpoint_handler in checkpoint_handlers {
+    match checkpoint_handler.get_latest_checkpoint_sequence_number().await {
+      Ok(seq) => return Ok(seq),
+      Err(e) => {
+        warn!("[fh-stream] failed to get the latest checkpoint: {}", e);
+        last_error = Some(e);
+      }
+    }
+  }
+
+  Err(last_error.expect("at least one endpoint"))
+}
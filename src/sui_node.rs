@@ -1,31 +1,279 @@
-use log::info;
+use std::{
+  collections::BTreeMap, fs::{self, OpenOptions}, net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr}, process::Stdio,
+  sync::Arc, time::{Duration, Instant},
+};
+use backoff::backoff::Backoff;
+use eyre::{Result, Report};
+use log::{debug, info, warn};
+use serde::Deserialize;
+use sui_json_rpc::api::ReadApiClient;
 use tokio::{
-  process::Command, sync::oneshot::{Receiver},
+  fs::File, io::{AsyncBufReadExt, AsyncRead, AsyncWriteExt, BufReader}, process::{Child, Command}, spawn,
+  sync::{mpsc::UnboundedReceiver, oneshot::{Receiver}, watch, Mutex}, time::{sleep, timeout},
+};
+use crate::{
+  config::{NodeConfig, RestartConfig, RestartPolicy}, runtime::FirehoseStreamer, health::HEALTH,
 };
 
+const READY_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// The only part of the sui-node config file we need
+#[derive(Deserialize)]
+struct SuiNodeConfig {
+  #[serde(rename = "json-rpc-address", default = "default_json_rpc_address")]
+  json_rpc_address: SocketAddr,
+}
+
+fn default_json_rpc_address() -> SocketAddr {
+  "0.0.0.0:9000".parse().unwrap()
+}
+
+/// Reads the JSON-RPC listen address from the sui-node config file. A node listening on all interfaces is reached
+/// through localhost.
+pub fn read_rpc_url(config_path: &str) -> Result<String> {
+  let content = fs::read_to_string(config_path).map_err(|e| {
+    Report::msg(format!("Failed to read sui-node config file {}: {}", config_path, e))
+  })?;
+  let config = serde_yaml::from_str::<SuiNodeConfig>(&content).map_err(|e| {
+    Report::msg(format!("Failed to parse sui-node config file {}: {}", config_path, e))
+  })?;
+
+  let mut address = config.json_rpc_address;
+  if address.ip().is_unspecified() {
+    let localhost: IpAddr = match address {
+      SocketAddr::V4(_) => Ipv4Addr::LOCALHOST.into(),
+      SocketAddr::V6(_) => Ipv6Addr::LOCALHOST.into(),
+    };
+    address.set_ip(localhost);
+  }
+
+  Ok(format!("http://{}", address))
+}
+
+/// Where the sui-node output is forwarded to. It never goes to stdout, which only carries the Firehose protocol.
+#[derive(Clone)]
+enum LogSink {
+  Stderr,
+  File(Arc<Mutex<File>>),
+}
+
+impl LogSink {
+  fn open(log_path: Option<&str>) -> Result<Self> {
+    let log_path = match log_path {
+      Some(log_path) => log_path,
+      None => return Ok(LogSink::Stderr),
+    };
+
+    let file = OpenOptions::new().create(true).append(true).open(log_path).map_err(|e| {
+      Report::msg(format!("Failed to open sui-node log file {}: {}", log_path, e))
+    })?;
+
+    Ok(LogSink::File(Arc::new(Mutex::new(File::from_std(file)))))
+  }
+
+  async fn write_line(&self, prefix: &str, line: &str) {
+    match self {
+      LogSink::Stderr => eprintln!("{} {}", prefix, line),
+      LogSink::File(file) => {
+        let line = format!("{} {}\n", prefix, line);
+
+        if let Err(e) = file.lock().await.write_all(line.as_bytes()).await {
+          warn!("Failed to write to the sui-node log file: {}", e);
+        }
+      }
+    }
+  }
+
+  /// Forwards every line of `source` until it is closed i.e. when the child process exits, or reading it fails. Lines
+  /// that are not valid UTF-8 are forwarded with the invalid bytes replaced.
+  fn forward<R: AsyncRead + Unpin + Send + 'static>(&self, prefix: String, source: R) {
+    let sink = self.clone();
+
+    spawn(async move {
+      let mut reader = BufReader::new(source);
+      let mut line = vec![];
+
+      loop {
+        line.clear();
+
+        match reader.read_until(b'\n', &mut line).await {
+          Ok(0) => break,
+          Ok(_) => {
+            let line = String::from_utf8_lossy(&line);
+            sink.write_line(&prefix, line.trim_end_matches(['\n', '\r'])).await;
+          },
+          Err(e) => {
+            // A failing pipe usually keeps failing, so stop forwarding rather than spin on the error
+            warn!("Failed to read the sui-node output, no longer forwarding it: {}", e);
+            break
+          },
+        }
+      }
+    });
+  }
+}
+
 pub struct SuiNode {
+  binary: String,
   config_path: String,
+  args: Vec<String>,
+  env: BTreeMap<String, String>,
+  log_path: Option<String>,
+  log_prefix: String,
+  rpc_url: String,
+  ready_timeout: Duration,
+  restart: RestartConfig,
 }
 
 impl SuiNode {
-  pub fn new(config_path: String) -> Self {
-    Self {config_path}
+  pub fn new(config: &NodeConfig, rpc_url: String) -> Self {
+    Self {
+      binary: config.binary.clone(),
+      config_path: config.config_path.clone().expect("sui-node config should be provided"),
+      args: config.args.clone(),
+      env: config.env.clone(),
+      log_path: config.log_path.clone(),
+      log_prefix: config.log_prefix.clone(),
+      rpc_url,
+      ready_timeout: Duration::from_secs(config.ready_timeout_secs),
+      restart: config.restart.clone(),
+    }
   }
 
-  pub async fn start(&self, rx: Receiver<()>) {
-    let mut child = Command::new("sui-node")
+  /// Runs the sui-node and restarts it according to the restart policy, or right away when asked to through
+  /// `restart`. `ready` is set while the child process is up and answers RPC calls so that the streamer can pause
+  /// in the meantime. Returns once `rx` fires, or with an error once the node should not be restarted anymore.
+  pub async fn start(
+    &self,
+    mut rx: Receiver<()>,
+    ready: watch::Sender<bool>,
+    mut restart: UnboundedReceiver<()>,
+  ) -> Result<()> {
+    let mut backoff = self.restart.backoff();
+    let mut attempts = 0;
+    Self::set_ready(&ready, false);
+    let log_sink = LogSink::open(self.log_path.as_deref())?;
+
+    loop {
+      let mut child = self.spawn_child(&log_sink)?;
+
+      let started_at = Instant::now();
+      let mut answering = false;
+
+      let status = loop {
+        tokio::select! {
+          status = child.wait() => break Some(status?),
+          Some(()) = restart.recv() => {
+            warn!("Restarting Sui Node on request");
+            child.kill().await.expect("kill sui-node");
+            break None
+          }
+          _ = &mut rx => {
+            info!("Killing Sui Node");
+            child.kill().await.expect("kill sui-node");
+            Self::set_ready(&ready, false);
+
+            return Ok(())
+          },
+          result = self.wait_for_rpc(), if !answering => {
+            if let Err(e) = result {
+              child.kill().await.expect("kill sui-node");
+              return Err(e)
+            }
+
+            info!("Sui Node is answering on {}", self.rpc_url);
+            answering = true;
+            Self::set_ready(&ready, true);
+          }
+        }
+      };
+
+      Self::set_ready(&ready, false);
+
+      let status = match status {
+        Some(status) => status,
+        None => continue,
+      };
+
+      if started_at.elapsed() >= Duration::from_secs(self.restart.reset_after_secs) {
+        backoff.reset();
+        attempts = 0;
+      }
+
+      let should_restart = match self.restart.policy {
+        RestartPolicy::Never => false,
+        RestartPolicy::OnFailure => !status.success(),
+        RestartPolicy::Always => true,
+      };
+
+      if !should_restart {
+        return Err(Report::msg(format!("Sui Node exited: {status:?}")))
+      }
+
+      if self.restart.max_attempts > 0 && attempts >= self.restart.max_attempts {
+        return Err(Report::msg(format!("Sui Node exited: {status:?}. Giving up after {attempts} restarts")))
+      }
+
+      attempts += 1;
+      let delay = backoff.next_backoff().unwrap_or(Duration::from_millis(self.restart.max_delay_ms));
+      warn!("Sui Node exited: {status:?}. Restarting in {delay:?}, attempt {attempts}");
+
+      tokio::select! {
+        _ = sleep(delay) => {}
+        _ = &mut rx => return Ok(()),
+      }
+    }
+  }
+
+  fn set_ready(ready: &watch::Sender<bool>, value: bool) {
+    let _ = ready.send(value);
+    HEALTH.set_node_ready(value);
+  }
+
+  fn spawn_child(&self, log_sink: &LogSink) -> Result<Child> {
+    let mut child = Command::new(&self.binary)
     .arg(format!("--config-path={}", self.config_path))
+    .args(&self.args)
+    .envs(&self.env)
+    .stdin(Stdio::null())
+    .stdout(Stdio::piped())
+    .stderr(Stdio::piped())
+    .kill_on_drop(true)
     .spawn()
-    .expect("start sui-node child process");
+    .map_err(|e| Report::msg(format!("Failed to start sui-node child process {}: {}", self.binary, e)))?;
+
+    if let Some(stdout) = child.stdout.take() {
+      log_sink.forward(self.log_prefix.clone(), stdout);
+    }
+    if let Some(stderr) = child.stderr.take() {
+      log_sink.forward(self.log_prefix.clone(), stderr);
+    }
+
+    Ok(child)
+  }
 
-    tokio::select! {
-      status = child.wait() => {
-        panic!("Sui Node exited: {status:?}");
+  /// Polls the node until it answers RPC calls
+  async fn wait_for_rpc(&self) -> Result<()> {
+    let http_client = FirehoseStreamer::get_http_client(&self.rpc_url)?;
+
+    timeout(self.ready_timeout, async {
+      loop {
+        match http_client.get_latest_checkpoint_sequence_number().await {
+          Ok(_) => return,
+          Err(e) => debug!("Sui Node is not answering on {} yet: {:?}", self.rpc_url, e),
+        }
+
+        sleep(READY_POLL_INTERVAL).await;
       }
-      _ = rx => {
-        info!("Killing Sui Node");
-        child.kill().await.expect("kill sui-node")
-      },
-    };
+    })
+    .await
+    .map_err(|_| {
+      Report::msg(format!(
+        "Sui Node did not answer on {} within {:?}. Check its logs and the json-rpc-address in {}",
+        self.rpc_url,
+        self.ready_timeout,
+        self.config_path,
+      ))
+    })
   }
 }
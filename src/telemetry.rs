@@ -0,0 +1,64 @@
+use std::io;
+use eyre::{Result, Report};
+use opentelemetry::{KeyValue, global, runtime, sdk::{Resource, trace}};
+use opentelemetry_otlp::WithExportConfig;
+use tracing::Level;
+use tracing_subscriber::{
+  EnvFilter, Layer, filter::{LevelFilter, Targets}, fmt::{self, format::FmtSpan}, layer::SubscriberExt,
+  util::SubscriberInitExt,
+};
+use crate::config::{LogConfig, LogFormat};
+
+/// Sets up logging to stderr, as stdout only carries the Firehose protocol, and the OpenTelemetry export of the
+/// spans if a collector endpoint is configured. Records of the `log` crate are forwarded as well.
+///
+/// Logs are filtered with `RUST_LOG`, which defaults to `info`. The checkpoint and RPC batch spans are at the
+/// debug level, so their duration is logged when they close with `RUST_LOG=sui_sf_indexer=debug`, while they are
+/// always exported.
+pub fn init(config: &LogConfig) -> Result<()> {
+  let env_filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info"));
+
+  let fmt_layer = match config.format {
+    LogFormat::Text => fmt::layer()
+    .with_writer(io::stderr)
+    .with_span_events(FmtSpan::CLOSE)
+    .boxed(),
+    LogFormat::Json => fmt::layer()
+    .json()
+    .with_writer(io::stderr)
+    .with_span_events(FmtSpan::CLOSE)
+    .boxed(),
+  }
+  .with_filter(env_filter);
+
+  let otel_layer = match &config.otlp_endpoint {
+    Some(endpoint) => {
+      let tracer = opentelemetry_otlp::new_pipeline()
+      .tracing()
+      .with_exporter(opentelemetry_otlp::new_exporter().tonic().with_endpoint(endpoint))
+      .with_trace_config(
+        trace::config().with_resource(Resource::new(vec![KeyValue::new("service.name", config.service_name.clone())]))
+      )
+      .install_batch(runtime::Tokio)
+      .map_err(|e| Report::msg(format!("Failed to set up the OpenTelemetry export to {}: {}", endpoint, e)))?;
+
+      let targets = Targets::new()
+      .with_target(env!("CARGO_CRATE_NAME"), Level::DEBUG)
+      .with_default(LevelFilter::INFO);
+
+      Some(tracing_opentelemetry::layer().with_tracer(tracer).with_filter(targets))
+    }
+    None => None,
+  };
+
+  tracing_subscriber::registry()
+  .with(fmt_layer)
+  .with(otel_layer)
+  .try_init()
+  .map_err(|e| Report::msg(format!("Failed to set up logging: {}", e)))
+}
+
+/// Exports the spans that have not been exported yet
+pub fn shutdown() {
+  global::shutdown_tracer_provider();
+}
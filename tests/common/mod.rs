@@ -0,0 +1,327 @@
+//! A mock fullnode serving the JSON-RPC methods the streamer uses from the fixture files in `tests/fixtures`,
+//! with faults that can be injected per method.
+//!
+//! The methods are served by a jsonrpsee server, while the faults are injected by a middleware in front of it as
+//! they happen at the HTTP level.
+
+use std::{
+  collections::{HashMap, VecDeque}, fs, future::Future, io::{self, Write}, net::SocketAddr, path::Path, pin::Pin,
+  sync::{Arc, Mutex}, task::{Context, Poll}, time::Duration,
+};
+use hyper::{Body, Request, Response, StatusCode, header::CONTENT_TYPE};
+use jsonrpsee::{RpcModule, core::Error as RpcError, server::{ServerBuilder, ServerHandle}};
+use serde_json::{Value, json};
+use sui_types::{
+  base_types::ObjectID, dynamic_field::Field, id::UID,
+  sui_system_state::{SuiSystemStateTrait, sui_system_state_inner_v1::SuiSystemStateInnerV1},
+};
+use tokio::time::sleep;
+use tower::{Layer, Service, ServiceBuilder};
+
+/// The methods the mock fullnode serves
+const METHODS: [&str; 5] = [
+  "sui_getCheckpoint",
+  "sui_getLatestCheckpointSequenceNumber",
+  "sui_multiGetTransactionBlocks",
+  "sui_tryMultiGetPastObjects",
+  "suix_getLatestSuiSystemState",
+];
+
+/// How the next call of a method fails
+#[derive(Debug, Clone, Copy)]
+pub enum Fault {
+  /// The response never comes, at least not within a test
+  Timeout,
+  /// The response has this HTTP status i.e. 429
+  Status(u16),
+  /// The response is not valid JSON
+  Malformed,
+}
+
+struct Fixtures {
+  checkpoints: Vec<Value>,
+  transactions: HashMap<String, Value>,
+  objects: Vec<Value>,
+  system_state: Value,
+  /// Served as the latest checkpoint instead of the last fixture, while the following ones can still be fetched
+  latest: Mutex<Option<u64>>,
+}
+
+impl Fixtures {
+  fn load(name: &str) -> Self {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures").join(name);
+    let read = |file: &str| -> Value {
+      let path = dir.join(file);
+      let content = fs::read_to_string(&path).unwrap_or_else(|e| panic!("read {}: {}", path.display(), e));
+      serde_json::from_str(&content).unwrap_or_else(|e| panic!("parse {}: {}", path.display(), e))
+    };
+    let read_all = |file: &str| -> Vec<Value> {
+      serde_json::from_value(read(file)).unwrap_or_else(|e| panic!("parse {}: {}", file, e))
+    };
+
+    let (system_state_object, system_state) = system_state(&read("system_state.json"));
+    let mut objects = read_all("objects.json");
+    objects.push(system_state_object);
+
+    Self {
+      checkpoints: read_all("checkpoints.json"),
+      transactions: read_all("transactions.json")
+      .into_iter()
+      .map(|tx| (tx["digest"].as_str().expect("transaction digest").to_string(), tx))
+      .collect(),
+      objects,
+      system_state,
+      latest: Mutex::new(None),
+    }
+  }
+}
+
+/// Builds the dynamic field of 0x5 holding the system state, with the BCS bytes the streamer decodes, and the
+/// summary `suix_getLatestSuiSystemState` serves from the `inner` system state of the fixture
+fn system_state(fixture: &Value) -> (Value, Value) {
+  let inner = serde_json::from_value::<SuiSystemStateInnerV1>(fixture["inner"].clone()).expect("system state fixture");
+  let mut object = fixture["object"].clone();
+  let id = ObjectID::from_hex_literal(object["objectId"].as_str().expect("object id")).expect("valid object id");
+
+  let field = Field {id: UID::new(id), name: inner.system_state_version, value: inner.clone()};
+  object["bcs"] = json!({
+    "dataType": "moveObject",
+    "type": object["type"],
+    "hasPublicTransfer": false,
+    "version": as_u64(&object["version"]).expect("object version"),
+    "bcsBytes": base64::encode(bcs::to_bytes(&field).expect("BCS system state")),
+  });
+
+  let summary = serde_json::to_value(inner.into_sui_system_state_summary()).expect("system state summary");
+
+  (object, summary)
+}
+
+#[derive(Default)]
+struct State {
+  faults: HashMap<String, VecDeque<Fault>>,
+  calls: HashMap<String, usize>,
+}
+
+pub struct MockFullnode {
+  pub url: String,
+  fixtures: Arc<Fixtures>,
+  state: Arc<Mutex<State>>,
+  /// The server stops once this is dropped
+  _handle: ServerHandle,
+}
+
+impl MockFullnode {
+  /// Serves the fixtures of `tests/fixtures/<name>` on a random local port
+  pub async fn start(name: &str) -> Self {
+    let fixtures = Arc::new(Fixtures::load(name));
+    let state = Arc::new(Mutex::new(State::default()));
+
+    let mut module = RpcModule::new(Arc::clone(&fixtures));
+    for method in METHODS {
+      module.register_method(method, move |params, fixtures| {
+        let params = params.parse::<Value>().unwrap_or(Value::Null);
+        dispatch(fixtures, method, &params).map_err(RpcError::Custom)
+      })
+      .expect("register method");
+    }
+
+    let server = ServerBuilder::default()
+    .set_middleware(ServiceBuilder::new().layer(FaultLayer(Arc::clone(&state))))
+    .build(SocketAddr::from(([127, 0, 0, 1], 0)))
+    .await
+    .expect("mock fullnode server");
+    let url = format!("http://{}", server.local_addr().expect("local address"));
+    let handle = server.start(module).expect("start mock fullnode");
+
+    Self {url, fixtures, state, _handle: handle}
+  }
+
+  /// Reports `seq` as the latest checkpoint, as if the fullnode was still syncing the following ones
+  pub fn set_latest(&self, seq: u64) {
+    *self.fixtures.latest.lock().unwrap() = Some(seq);
+  }
+
+  /// Fails the next `times` calls of `method` with `fault`
+  pub fn inject(&self, method: &str, fault: Fault, times: usize) {
+    let mut state = self.state.lock().unwrap();
+    let faults = state.faults.entry(method.to_string()).or_default();
+
+    for _ in 0..times {
+      faults.push_back(fault);
+    }
+  }
+
+  /// The number of calls of `method` so far, including the failed ones
+  pub fn calls(&self, method: &str) -> usize {
+    self.state.lock().unwrap().calls.get(method).copied().unwrap_or_default()
+  }
+
+  /// The fixture of a checkpoint, as served
+  pub fn checkpoint(&self, seq: u64) -> Value {
+    dispatch(&self.fixtures, "sui_getCheckpoint", &json!([seq])).expect("checkpoint fixture")
+  }
+
+  /// The fixture of a transaction, as served
+  pub fn transaction(&self, digest: &str) -> Value {
+    self.fixtures.transactions.get(digest).cloned().expect("transaction fixture")
+  }
+
+  /// The digest of the genesis checkpoint, which the streamer checks the chain id against
+  pub fn chain_id(&self) -> String {
+    self.checkpoint(0)["digest"].as_str().expect("checkpoint digest").to_string()
+  }
+}
+
+/// Fails the calls of the methods that have faults injected, and passes the other ones on to the server
+#[derive(Clone)]
+struct FaultLayer(Arc<Mutex<State>>);
+
+impl<S> Layer<S> for FaultLayer {
+  type Service = FaultService<S>;
+
+  fn layer(&self, inner: S) -> Self::Service {
+    FaultService {inner, state: Arc::clone(&self.0)}
+  }
+}
+
+#[derive(Clone)]
+struct FaultService<S> {
+  inner: S,
+  state: Arc<Mutex<State>>,
+}
+
+impl<S> Service<Request<Body>> for FaultService<S>
+where
+  S: Service<Request<Body>, Response = Response<Body>> + Clone + Send + 'static,
+  S::Future: Send,
+{
+  type Response = Response<Body>;
+  type Error = S::Error;
+  type Future = Pin<Box<dyn Future<Output = Result<Response<Body>, S::Error>> + Send>>;
+
+  fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), S::Error>> {
+    self.inner.poll_ready(cx)
+  }
+
+  fn call(&mut self, request: Request<Body>) -> Self::Future {
+    let state = Arc::clone(&self.state);
+    let mut inner = self.inner.clone();
+
+    Box::pin(async move {
+      let (parts, body) = request.into_parts();
+      let body = hyper::body::to_bytes(body).await.expect("request body");
+      let method = serde_json::from_slice::<Value>(&body)
+      .ok()
+      .and_then(|request| request["method"].as_str().map(str::to_string))
+      .unwrap_or_default();
+
+      let fault = {
+        let mut state = state.lock().unwrap();
+        *state.calls.entry(method.clone()).or_default() += 1;
+        state.faults.get_mut(&method).and_then(|faults| faults.pop_front())
+      };
+
+      let response = match fault {
+        Some(Fault::Timeout) => {
+          sleep(Duration::from_secs(3600)).await;
+          Response::builder().status(StatusCode::GATEWAY_TIMEOUT).body(Body::empty())
+        }
+        Some(Fault::Status(status)) => Response::builder()
+        .status(StatusCode::from_u16(status).expect("valid status"))
+        .body(Body::from("injected fault")),
+        Some(Fault::Malformed) => Response::builder()
+        .header(CONTENT_TYPE, "application/json")
+        .body(Body::from("{\"jsonrpc\": \"2.0\", \"result\": ")),
+        None => return inner.call(Request::from_parts(parts, Body::from(body))).await,
+      };
+
+      Ok(response.expect("valid response"))
+    })
+  }
+}
+
+/// Sequence numbers and versions are sent either as numbers or as strings
+fn as_u64(value: &Value) -> Option<u64> {
+  value.as_u64().or_else(|| value.as_str()?.parse().ok())
+}
+
+/// Object ids are compared without their leading zeros i.e. 0x6 and 0x00..06
+fn normalize_id(value: &Value) -> String {
+  value.as_str().unwrap_or_default().trim_start_matches("0x").trim_start_matches('0').to_lowercase()
+}
+
+fn dispatch(fixtures: &Fixtures, method: &str, params: &Value) -> Result<Value, String> {
+  match method {
+    "sui_getCheckpoint" => {
+      let seq = as_u64(&params[0]).ok_or("invalid checkpoint id")?;
+
+      fixtures.checkpoints
+      .iter()
+      .find(|checkpoint| as_u64(&checkpoint["sequenceNumber"]) == Some(seq))
+      .cloned()
+      .ok_or_else(|| format!("Could not find the referenced checkpoint {}", seq))
+    }
+    "sui_getLatestCheckpointSequenceNumber" => {
+      let latest = fixtures.checkpoints.iter().filter_map(|checkpoint| as_u64(&checkpoint["sequenceNumber"])).max();
+      let latest = fixtures.latest.lock().unwrap().or(latest);
+
+      Ok(json!(latest.unwrap_or_default().to_string()))
+    }
+    "sui_multiGetTransactionBlocks" => {
+      let digests = params[0].as_array().ok_or("invalid digests")?;
+
+      digests
+      .iter()
+      .map(|digest| {
+        let digest = digest.as_str().unwrap_or_default();
+        fixtures.transactions.get(digest).cloned().ok_or_else(|| format!("Could not find transaction {}", digest))
+      })
+      .collect::<Result<Vec<_>, _>>()
+      .map(Value::Array)
+    }
+    "sui_tryMultiGetPastObjects" => {
+      let requests = params[0].as_array().ok_or("invalid past object requests")?;
+
+      Ok(Value::Array(requests.iter().map(|request| {
+        let object = fixtures.objects.iter().find(|object| {
+          normalize_id(&object["objectId"]) == normalize_id(&request["objectId"])
+          && as_u64(&object["version"]) == as_u64(&request["version"])
+        });
+
+        match object {
+          Some(object) => json!({"status": "VersionFound", "details": object}),
+          None => json!({"status": "VersionNotFound", "details": [request["objectId"], request["version"]]}),
+        }
+      }).collect()))
+    }
+    "suix_getLatestSuiSystemState" => Ok(fixtures.system_state.clone()),
+    _ => Err(format!("Method {} is not supported by the mock fullnode", method)),
+  }
+}
+
+/// Collects the streamer output
+#[derive(Clone, Default)]
+pub struct Output(Arc<Mutex<Vec<u8>>>);
+
+impl Output {
+  pub fn lines(&self) -> Vec<String> {
+    String::from_utf8(self.0.lock().unwrap().clone())
+    .expect("UTF-8 output")
+    .lines()
+    .filter(|line| !line.is_empty())
+    .map(str::to_string)
+    .collect()
+  }
+}
+
+impl Write for Output {
+  fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+    self.0.lock().unwrap().extend_from_slice(buf);
+    Ok(buf.len())
+  }
+
+  fn flush(&mut self) -> io::Result<()> {
+    Ok(())
+  }
+}
@@ -0,0 +1,123 @@
+//! Golden tests for the converters. Each fixture of `tests/fixtures/convert` is converted and the resulting
+//! protobuf, rendered as JSON, is compared to its golden file in `tests/golden`. Run with `UPDATE_GOLDEN=1` to
+//! regenerate the golden files after an intended change, and review their diff.
+
+use std::{env, fs, path::{Path, PathBuf}};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use serde_json::Value;
+use sui_indexer::{models::objects::ObjectStatus, types::CheckpointTransactionBlockResponse};
+use sui_json_rpc_types::{Checkpoint, SuiObjectData, SuiTransactionBlockResponse};
+use sui_sf_indexer::convert::{
+  checkpoint::convert_checkpoint, common::{convert_sui_object_response_error, convert_type_tag_str},
+  object::convert_object_change, tx::convert_transaction,
+};
+use sui_types::error::SuiObjectResponseError;
+
+const UPDATE_GOLDEN: &str = "UPDATE_GOLDEN";
+
+fn root() -> &'static Path {
+  Path::new(env!("CARGO_MANIFEST_DIR"))
+}
+
+fn fixture<T: DeserializeOwned>(name: &str) -> Vec<T> {
+  let path = root().join("tests/fixtures/convert").join(format!("{}.json", name));
+  let content = fs::read_to_string(&path).unwrap_or_else(|e| panic!("read {}: {}", path.display(), e));
+
+  serde_json::from_str(&content).unwrap_or_else(|e| panic!("parse {}: {}", path.display(), e))
+}
+
+fn golden_path(name: &str) -> PathBuf {
+  root().join("tests/golden").join(format!("{}.json", name))
+}
+
+/// Compares the converted fixtures to their golden file, which is written instead when `UPDATE_GOLDEN` is set. A
+/// missing golden file fails the test, so that a golden file that is not committed can't pass silently.
+fn assert_golden<T: Serialize>(name: &str, converted: &[T]) {
+  let actual = serde_json::to_value(converted).expect("converted fixtures should serialize");
+  let path = golden_path(name);
+
+  if env::var_os(UPDATE_GOLDEN).is_some() {
+    fs::create_dir_all(path.parent().unwrap()).expect("create the golden directory");
+    fs::write(&path, serde_json::to_string_pretty(&actual).unwrap() + "\n")
+    .unwrap_or_else(|e| panic!("write {}: {}", path.display(), e));
+    eprintln!("wrote {}", path.display());
+
+    return
+  }
+
+  assert!(path.exists(), "{} is missing, run with {}=1 to write it", path.display(), UPDATE_GOLDEN);
+
+  let content = fs::read_to_string(&path).unwrap_or_else(|e| panic!("read {}: {}", path.display(), e));
+  let expected: Value = serde_json::from_str(&content).unwrap_or_else(|e| panic!("parse {}: {}", path.display(), e));
+
+  assert!(
+    actual == expected,
+    "{} no longer matches {}, run with {}=1 to regenerate it if the change is intended:\n{}",
+    name,
+    path.display(),
+    UPDATE_GOLDEN,
+    serde_json::to_string_pretty(&actual).unwrap(),
+  );
+}
+
+#[test]
+fn checkpoints() {
+  let checkpoints = fixture::<Checkpoint>("checkpoints");
+
+  assert_golden("checkpoints", &checkpoints.iter().map(convert_checkpoint).collect::<Vec<_>>());
+}
+
+/// Covers every transaction kind, command, call argument and owner
+#[test]
+fn transactions() {
+  let transactions = fixture::<SuiTransactionBlockResponse>("transactions")
+  .into_iter()
+  .map(|tx| CheckpointTransactionBlockResponse::try_from(tx).expect("fixture should be a full transaction"))
+  .collect::<Vec<_>>();
+
+  assert_golden("transactions", &transactions.iter().map(convert_transaction).collect::<Vec<_>>());
+}
+
+#[derive(Deserialize)]
+struct ObjectChange {
+  status: String,
+  data: SuiObjectData,
+}
+
+fn object_status(status: &str) -> ObjectStatus {
+  match status {
+    "Created" => ObjectStatus::Created,
+    "Mutated" => ObjectStatus::Mutated,
+    "Deleted" => ObjectStatus::Deleted,
+    "Wrapped" => ObjectStatus::Wrapped,
+    "Unwrapped" => ObjectStatus::Unwrapped,
+    "UnwrappedThenDeleted" => ObjectStatus::UnwrappedThenDeleted,
+    _ => panic!("unknown object status {}", status),
+  }
+}
+
+/// Covers every object status, object type, parsed and raw content, and Move value
+#[test]
+fn changed_objects() {
+  let changed_objects = fixture::<ObjectChange>("objects")
+  .into_iter()
+  .map(|change| (object_status(&change.status), change.data))
+  .collect::<Vec<_>>();
+
+  assert_golden("objects", &changed_objects.iter().map(convert_object_change).collect::<Vec<_>>());
+}
+
+#[test]
+fn object_errors() {
+  let errors = fixture::<SuiObjectResponseError>("object_errors");
+
+  assert_golden("object_errors", &errors.iter().map(convert_sui_object_response_error).collect::<Vec<_>>());
+}
+
+/// Covers every type tag, along with types that fail to parse
+#[test]
+fn type_tags() {
+  let type_tags = fixture::<String>("type_tags");
+
+  assert_golden("type_tags", &type_tags.iter().map(|t| convert_type_tag_str(t)).collect::<Vec<_>>());
+}
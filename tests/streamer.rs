@@ -0,0 +1,335 @@
+mod common;
+
+use std::{env, fs, process, sync::{Arc, Mutex}, time::Duration};
+use eyre::Result;
+use prost::Message;
+use sui_sf_indexer::{
+  config::Config, filter::expr::FilterExpr, pb::sui::checkpoint as pb, position::CheckpointPosition,
+  processor::{CheckpointProcessor, ErrorPolicy}, runtime::FirehoseStreamer,
+};
+use tokio::{sync::watch, time::timeout};
+use common::{Fault, MockFullnode, Output};
+
+const FIXTURES: &str = "fullnode";
+
+fn config(fullnode: &MockFullnode, start: u64, stop: u64) -> Config {
+  let mut config = Config {
+    chain_id: fullnode.chain_id(),
+    starting_checkpoint_seq: CheckpointPosition::Seq(start),
+    stop_checkpoint_seq: Some(CheckpointPosition::Seq(stop)),
+    ..Default::default()
+  };
+  config.watchdog.stall_timeout_secs = 0;
+  config.retry.initial_interval_ms = 10;
+  config.retry.max_interval_ms = 50;
+  config.retry.max_elapsed_time_secs = 10;
+
+  config
+}
+
+/// Streams with `config` and returns the result along with the emitted lines
+async fn stream(fullnode: &MockFullnode, config: &Config) -> (Result<()>, Vec<String>) {
+  stream_with(FirehoseStreamer::new(fullnode.url.clone(), config)).await
+}
+
+async fn stream_with(firehose_streamer: FirehoseStreamer) -> (Result<()>, Vec<String>) {
+  let output = Output::default();
+  let (_shutdown_tx, shutdown_rx) = watch::channel(false);
+  let mut firehose_streamer = firehose_streamer.with_output(output.clone());
+
+  let result = timeout(Duration::from_secs(60), firehose_streamer.start(shutdown_rx))
+  .await
+  .expect("streaming should finish");
+
+  (result, output.lines())
+}
+
+/// Keeps the framing lines as they are and reduces the payload lines to their kind
+fn frame(line: &str) -> String {
+  match line.split(' ').collect::<Vec<_>>().as_slice() {
+    ["FIRE", "INIT" | "BLOCK_START" | "BLOCK_END", ..] => line.to_string(),
+    ["FIRE", kind, _payload] => format!("FIRE {}", kind),
+    _ => panic!("unexpected line {}", line),
+  }
+}
+
+fn frames(lines: &[String]) -> Vec<String> {
+  lines.iter().map(|line| frame(line)).collect()
+}
+
+/// Decodes the payloads of the lines of this kind
+fn payloads<M: Message + Default>(lines: &[String], kind: &str) -> Vec<M> {
+  let prefix = format!("FIRE {} ", kind);
+
+  lines
+  .iter()
+  .filter_map(|line| line.strip_prefix(&prefix))
+  .map(|payload| M::decode(base64::decode(payload).expect("base64 payload").as_slice()).expect("protobuf payload"))
+  .collect()
+}
+
+#[tokio::test]
+async fn streams_checkpoints_as_fire_lines() {
+  let fullnode = MockFullnode::start(FIXTURES).await;
+  let (result, lines) = stream(&fullnode, &config(&fullnode, 1, 2)).await;
+  result.expect("streaming should succeed");
+
+  let mut expected = vec![
+    format!("FIRE INIT sui-node {} sui 0 0 {}", env!("CARGO_PKG_VERSION"), fullnode.chain_id()),
+  ];
+  for seq in 1..=2 {
+    expected.extend([
+      format!("FIRE BLOCK_START {}", seq),
+      "FIRE CHECKPOINT".to_string(),
+      "FIRE CHECKPOINT_STATS".to_string(),
+      "FIRE TRX".to_string(),
+      "FIRE OBJ".to_string(),
+      format!("FIRE BLOCK_END {}", seq),
+    ]);
+  }
+  assert_eq!(frames(&lines), expected);
+
+  let checkpoints = payloads::<pb::Checkpoint>(&lines, "CHECKPOINT");
+  let transactions = payloads::<pb::CheckpointTransactionBlockResponse>(&lines, "TRX");
+  let changed_objects = payloads::<pb::ChangedObject>(&lines, "OBJ");
+
+  for (index, seq) in (1..=2).enumerate() {
+    let fixture = fullnode.checkpoint(seq);
+
+    assert_eq!(checkpoints[index].sequence_number, seq);
+    assert_eq!(checkpoints[index].digest, fixture["digest"].as_str().unwrap());
+    assert_eq!(transactions[index].digest, fixture["transactions"][0].as_str().unwrap());
+
+    let changed_object = &changed_objects[index];
+    assert_eq!(
+      changed_object.status.as_ref().and_then(|status| status.object_status.clone()),
+      Some(pb::object_status::ObjectStatus::Mutated(())),
+    );
+    assert_eq!(changed_object.data.as_ref().map(|data| data.version), Some(seq + 1));
+  }
+
+  for stats in payloads::<pb::CheckpointStats>(&lines, "CHECKPOINT_STATS") {
+    assert_eq!(stats.transactions, 1);
+    assert_eq!(stats.successful_transactions, 1);
+    assert_eq!(stats.mutated_objects, 1);
+  }
+}
+
+/// The framing of a checkpoint whose payload lines are `kinds`
+fn block(seq: u64, kinds: &[&str]) -> Vec<String> {
+  let mut lines = vec![format!("FIRE BLOCK_START {}", seq)];
+  lines.extend(kinds.iter().map(|kind| format!("FIRE {}", kind)));
+  lines.push(format!("FIRE BLOCK_END {}", seq));
+
+  lines
+}
+
+fn init(fullnode: &MockFullnode) -> String {
+  format!("FIRE INIT sui-node {} sui 0 0 {}", env!("CARGO_PKG_VERSION"), fullnode.chain_id())
+}
+
+#[tokio::test]
+async fn streams_epoch_changes_with_the_system_state() {
+  let fullnode = MockFullnode::start(FIXTURES).await;
+  let (result, lines) = stream(&fullnode, &config(&fullnode, 3, 4)).await;
+  result.expect("streaming should succeed");
+
+  let mut expected = vec![init(&fullnode)];
+  expected.extend(block(3, &["CHECKPOINT", "CHECKPOINT_STATS", "TRX", "OBJ"]));
+  expected.extend(block(4, &["CHECKPOINT", "CHECKPOINT_STATS", "TRX", "OBJ", "EPOCH", "SYSTEM_STATE"]));
+  assert_eq!(frames(&lines), expected);
+
+  let checkpoint = fullnode.checkpoint(4);
+  let change_epoch = fullnode.transaction(checkpoint["transactions"][0].as_str().unwrap());
+  let epoch_start_timestamp_ms = change_epoch["transaction"]["data"]["transaction"]["epoch_start_timestamp_ms"]
+  .as_str()
+  .and_then(|timestamp| timestamp.parse::<u64>().ok())
+  .expect("epoch start timestamp");
+
+  let epoch_changes = payloads::<pb::EpochChange>(&lines, "EPOCH");
+  assert_eq!(epoch_changes.len(), 1);
+  assert_eq!(epoch_changes[0].epoch, 0);
+  assert_eq!(epoch_changes[0].end_checkpoint, 4);
+
+  let system_states = payloads::<pb::SuiSystemStateSummary>(&lines, "SYSTEM_STATE");
+  assert_eq!(system_states.len(), 1);
+  assert_eq!(system_states[0].epoch, 1);
+  assert_eq!(system_states[0].epoch_start_timestamp_ms, epoch_start_timestamp_ms);
+  assert_eq!(system_states[0].reference_gas_price, 1000);
+}
+
+#[tokio::test]
+async fn streams_empty_checkpoints() {
+  let fullnode = MockFullnode::start(FIXTURES).await;
+  let (result, lines) = stream(&fullnode, &config(&fullnode, 5, 5)).await;
+  result.expect("streaming should succeed");
+
+  let mut expected = vec![init(&fullnode)];
+  expected.extend(block(5, &["CHECKPOINT", "CHECKPOINT_STATS"]));
+  assert_eq!(frames(&lines), expected);
+
+  let stats = payloads::<pb::CheckpointStats>(&lines, "CHECKPOINT_STATS");
+  assert_eq!(stats[0].transactions, 0);
+}
+
+#[tokio::test]
+async fn streams_filtered_checkpoints() {
+  let fullnode = MockFullnode::start(FIXTURES).await;
+  let mut config = config(&fullnode, 1, 4);
+  config.filter.expression = Some(FilterExpr::parse("kind == change_epoch").expect("valid filter expression"));
+  let (result, lines) = stream(&fullnode, &config).await;
+  result.expect("streaming should succeed");
+
+  // Checkpoints are emitted even if none of their transactions pass the filter
+  let mut expected = vec![init(&fullnode)];
+  for seq in 1..=3 {
+    expected.extend(block(seq, &["CHECKPOINT", "CHECKPOINT_STATS"]));
+  }
+  expected.extend(block(4, &["CHECKPOINT", "CHECKPOINT_STATS", "TRX", "OBJ", "EPOCH", "SYSTEM_STATE"]));
+  assert_eq!(frames(&lines), expected);
+
+  let transactions = payloads::<pb::CheckpointTransactionBlockResponse>(&lines, "TRX");
+  assert_eq!(transactions[0].digest, fullnode.checkpoint(4)["transactions"][0].as_str().unwrap());
+}
+
+#[tokio::test]
+async fn stops_at_a_position_reached_while_streaming() {
+  let fullnode = MockFullnode::start(FIXTURES).await;
+  // Epoch 0 has not ended as far as the fullnode knows, so the stop can't be resolved before streaming starts
+  fullnode.set_latest(4);
+  let mut config = config(&fullnode, 3, 0);
+  config.stop_checkpoint_seq = Some(CheckpointPosition::Epoch(0));
+  let (result, lines) = stream(&fullnode, &config).await;
+  result.expect("streaming should succeed");
+
+  // Checkpoint 5 starts epoch 1, so the stream ends with checkpoint 4 without emitting it
+  let checkpoints = payloads::<pb::Checkpoint>(&lines, "CHECKPOINT");
+  assert_eq!(checkpoints.iter().map(|checkpoint| checkpoint.sequence_number).collect::<Vec<_>>(), vec![3, 4]);
+  assert_eq!(frames(&lines).last().map(String::as_str), Some("FIRE BLOCK_END 4"));
+}
+
+#[tokio::test]
+async fn resumes_from_the_cursor() {
+  let fullnode = MockFullnode::start(FIXTURES).await;
+  let cursor_path = env::temp_dir().join(format!("sui-sf-indexer-cursor-{}", process::id()));
+  fs::write(&cursor_path, "2").expect("write cursor");
+
+  let mut config = config(&fullnode, 1, 4);
+  config.cursor_path = Some(cursor_path.to_string_lossy().into_owned());
+  let (result, lines) = stream(&fullnode, &config).await;
+  let cursor = fs::read_to_string(&cursor_path).expect("read cursor");
+  fs::remove_file(&cursor_path).expect("remove cursor");
+  result.expect("streaming should succeed");
+
+  let checkpoints = payloads::<pb::Checkpoint>(&lines, "CHECKPOINT");
+  assert_eq!(checkpoints.iter().map(|checkpoint| checkpoint.sequence_number).collect::<Vec<_>>(), vec![2, 3, 4]);
+  // The cursor holds the next checkpoint to stream
+  assert_eq!(cursor.trim(), "5");
+}
+
+/// Records the checkpoints and transactions it is called with
+#[derive(Clone, Default)]
+struct RecordingProcessor(Arc<Mutex<Vec<String>>>);
+
+impl CheckpointProcessor for RecordingProcessor {
+  fn on_checkpoint_start(&mut self, checkpoint: &pb::Checkpoint) -> Result<()> {
+    self.0.lock().unwrap().push(format!("start {}", checkpoint.sequence_number));
+    Ok(())
+  }
+
+  fn on_transaction(
+    &mut self,
+    checkpoint: &pb::Checkpoint,
+    transaction: &pb::CheckpointTransactionBlockResponse,
+  ) -> Result<()> {
+    self.0.lock().unwrap().push(format!("transaction {} {}", checkpoint.sequence_number, transaction.digest));
+    Ok(())
+  }
+
+  fn on_checkpoint_end(&mut self, checkpoint: &pb::Checkpoint) -> Result<()> {
+    self.0.lock().unwrap().push(format!("end {}", checkpoint.sequence_number));
+    Ok(())
+  }
+}
+
+#[tokio::test]
+async fn runs_processors_on_every_checkpoint() {
+  let fullnode = MockFullnode::start(FIXTURES).await;
+  let processor = RecordingProcessor::default();
+  let firehose_streamer = FirehoseStreamer::new(fullnode.url.clone(), &config(&fullnode, 4, 5))
+  .with_processor(processor.clone(), ErrorPolicy::Stop);
+  let (result, _) = stream_with(firehose_streamer).await;
+  result.expect("streaming should succeed");
+
+  let change_epoch = fullnode.checkpoint(4)["transactions"][0].as_str().unwrap().to_string();
+  assert_eq!(*processor.0.lock().unwrap(), vec![
+    "start 4".to_string(),
+    format!("transaction 4 {}", change_epoch),
+    "end 4".to_string(),
+    "start 5".to_string(),
+    "end 5".to_string(),
+  ]);
+}
+
+#[tokio::test]
+async fn retries_rate_limited_calls() {
+  let clean = MockFullnode::start(FIXTURES).await;
+  let (result, expected) = stream(&clean, &config(&clean, 1, 2)).await;
+  result.expect("streaming should succeed");
+
+  let fullnode = MockFullnode::start(FIXTURES).await;
+  fullnode.inject("sui_getCheckpoint", Fault::Status(429), 2);
+  fullnode.inject("sui_multiGetTransactionBlocks", Fault::Status(429), 2);
+  let (result, lines) = stream(&fullnode, &config(&fullnode, 1, 2)).await;
+  result.expect("streaming should recover from rate limiting");
+
+  assert_eq!(lines, expected);
+  // A failed download is retried as a whole, so every method may be called more than once per fault
+  assert!(fullnode.calls("sui_getCheckpoint") >= clean.calls("sui_getCheckpoint") + 2);
+  assert!(fullnode.calls("sui_multiGetTransactionBlocks") >= clean.calls("sui_multiGetTransactionBlocks") + 2);
+}
+
+#[tokio::test]
+async fn retries_malformed_responses() {
+  let clean = MockFullnode::start(FIXTURES).await;
+  let (result, expected) = stream(&clean, &config(&clean, 1, 2)).await;
+  result.expect("streaming should succeed");
+
+  let fullnode = MockFullnode::start(FIXTURES).await;
+  fullnode.inject("sui_tryMultiGetPastObjects", Fault::Malformed, 1);
+  let (result, lines) = stream(&fullnode, &config(&fullnode, 1, 2)).await;
+  result.expect("streaming should recover from a malformed response");
+
+  assert_eq!(lines, expected);
+  assert!(fullnode.calls("sui_tryMultiGetPastObjects") > clean.calls("sui_tryMultiGetPastObjects"));
+}
+
+#[tokio::test]
+async fn recovers_from_timeouts_with_the_watchdog() {
+  let clean = MockFullnode::start(FIXTURES).await;
+  let (result, expected) = stream(&clean, &config(&clean, 1, 2)).await;
+  result.expect("streaming should succeed");
+
+  let fullnode = MockFullnode::start(FIXTURES).await;
+  fullnode.inject("sui_multiGetTransactionBlocks", Fault::Timeout, 1);
+  let mut config = config(&fullnode, 1, 2);
+  config.watchdog.stall_timeout_secs = 1;
+  let (result, lines) = stream(&fullnode, &config).await;
+  result.expect("streaming should recover from a timeout");
+
+  assert_eq!(lines, expected);
+}
+
+#[tokio::test]
+async fn fails_once_retries_give_up() {
+  let fullnode = MockFullnode::start(FIXTURES).await;
+  fullnode.inject("sui_multiGetTransactionBlocks", Fault::Status(500), 1000);
+  let mut config = config(&fullnode, 1, 2);
+  config.retry.max_elapsed_time_secs = 1;
+  let (result, lines) = stream(&fullnode, &config).await;
+
+  assert!(result.is_err());
+  assert_eq!(frames(&lines), vec![
+    format!("FIRE INIT sui-node {} sui 0 0 {}", env!("CARGO_PKG_VERSION"), fullnode.chain_id()),
+  ]);
+}